@@ -0,0 +1,30 @@
+use bevy::prelude::*;
+
+use crate::animation::resources::AnimationState;
+
+// -- COMPONENTS --
+
+// plays animations from PlayerAnimations on a sprite sheet
+#[derive(Component, Default)]
+pub struct Animator {
+    pub state: AnimationState,
+    pub frame: usize,
+    // seconds spent on the current frame
+    pub elapsed: f32,
+    // whether the first frame's been shown yet, for its event
+    pub started: bool,
+    // a non-looping animation got to the end of its last frame
+    pub finished: bool,
+}
+
+impl Animator {
+    // starts over from the first frame, unless it's already playing
+    pub fn play(&mut self, state: AnimationState) {
+        if self.state != state {
+            *self = Animator {
+                state,
+                ..default()
+            };
+        }
+    }
+}
@@ -0,0 +1,34 @@
+// import bevy crates
+use bevy::prelude::*;
+
+// import modules
+pub mod components;
+pub mod resources;
+pub mod systems;
+
+// use modules
+use resources::*;
+use systems::*;
+use crate::data::load_ron;
+use crate::{ AppState, SimulationState };
+
+pub const PLAYER_ANIMATIONS_FILE: &str = "animations/player.ron";
+
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        // needed as soon as the player spawns, so it's read straight away like the campaign
+        let player_animations: AnimationSet = load_ron(PLAYER_ANIMATIONS_FILE).unwrap();
+
+        app.insert_resource(PlayerAnimations { set: player_animations })
+            .add_event::<AnimationEvent>()
+            // drawn on top of the simulation, so these follow the frame rate rather than ticks
+            .add_systems(
+                Update,
+                (choose_player_animation, animate_player.after(choose_player_animation)).run_if(
+                    in_state(AppState::Game).and_then(in_state(SimulationState::Running))
+                )
+            );
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+// -- RESOURCES --
+
+#[derive(Resource)]
+pub struct PlayerAnimations {
+    pub set: AnimationSet,
+}
+
+// -- ANIMATION DATA --
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum AnimationState {
+    #[default]
+    Idle,
+    Run,
+    JumpRise,
+    Fall,
+    Dash,
+    WallSlide,
+    Death,
+}
+
+// a sprite sheet and everything that can be played off it
+#[derive(Deserialize)]
+pub struct AnimationSet {
+    // path under assets/
+    pub image: String,
+    // in pixels
+    pub frame_size: (u32, u32),
+    pub columns: usize,
+    pub rows: usize,
+    // full width/height it's drawn at
+    pub size: (f32, f32),
+    pub animations: HashMap<AnimationState, AnimationData>,
+}
+
+#[derive(Deserialize)]
+pub struct AnimationData {
+    pub looping: bool,
+    pub frames: Vec<FrameData>,
+}
+
+#[derive(Deserialize)]
+pub struct FrameData {
+    // which frame of the sheet to show
+    pub index: usize,
+    // seconds
+    pub duration: f32,
+    #[serde(default)]
+    pub event: Option<String>,
+}
+
+impl AnimationSet {
+    pub fn sprite_sheet_bundle(
+        &self,
+        asset_server: &AssetServer,
+        texture_atlas_layouts: &mut Assets<TextureAtlasLayout>
+    ) -> SpriteSheetBundle {
+        let layout = TextureAtlasLayout::from_grid(
+            Vec2::new(self.frame_size.0 as f32, self.frame_size.1 as f32),
+            self.columns,
+            self.rows,
+            None,
+            None
+        );
+
+        SpriteSheetBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::new(self.size.0, self.size.1)),
+                ..default()
+            },
+            texture: asset_server.load(self.image.clone()),
+            atlas: TextureAtlas {
+                layout: texture_atlas_layouts.add(layout),
+                index: 0,
+            },
+            ..default()
+        }
+    }
+}
+
+// -- EVENTS --
+
+// a frame with an event just came up, for footstep sounds and the like
+#[derive(Event)]
+pub struct AnimationEvent {
+    // the sprite that's animating
+    pub entity: Entity,
+    pub name: String,
+}
@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::animation::components::*;
+use crate::animation::resources::*;
+use crate::interpolation::components::Interpolated;
+use crate::juice::resources::HitStopTimer;
+use crate::{ Dash, Direction, Player, PlayerDied };
+
+// how side-on a hit has to be to count as a wall
+const WALL_NORMAL: f32 = 0.7;
+
+// works out what the player's doing from the last tick and picks the animation to match
+pub fn choose_player_animation(
+    direction: Res<Direction>,
+    mut player_died_event_reader: EventReader<PlayerDied>,
+    player_query: Query<
+        (&Dash, &Interpolated, Option<&KinematicCharacterControllerOutput>, &Children),
+        With<Player>
+    >,
+    mut sprite_query: Query<(&mut Animator, &mut Sprite)>
+) {
+    let died = player_died_event_reader.read().last().is_some();
+
+    for (dash, interpolated, output, children) in player_query.iter() {
+        let grounded = output.is_some_and(|output| output.grounded);
+        let against_wall = output.is_some_and(|output| {
+            output.collisions.iter().any(|collision| {
+                collision.toi.details.is_some_and(|details| details.normal1.x.abs() > WALL_NORMAL)
+            })
+        });
+        let moved = interpolated.current - interpolated.previous;
+
+        let state = if dash.is_dashing {
+            AnimationState::Dash
+        } else if grounded {
+            if moved.x.abs() > 0.1 { AnimationState::Run } else { AnimationState::Idle }
+        } else if moved.y > 0.0 {
+            AnimationState::JumpRise
+        } else if against_wall {
+            AnimationState::WallSlide
+        } else {
+            AnimationState::Fall
+        };
+
+        for child in children.iter() {
+            if let Ok((mut animator, mut sprite)) = sprite_query.get_mut(*child) {
+                // the sheet faces right
+                sprite.flip_x = *direction == Direction::Left;
+
+                if died {
+                    // starts over even if it was already dying
+                    *animator = Animator {
+                        state: AnimationState::Death,
+                        ..default()
+                    };
+                } else if animator.state != AnimationState::Death || animator.finished {
+                    animator.play(state);
+                }
+            }
+        }
+    }
+}
+
+pub fn animate_player(
+    time: Res<Time>,
+    hit_stop_timer: Res<HitStopTimer>,
+    player_animations: Res<PlayerAnimations>,
+    mut animation_event_writer: EventWriter<AnimationEvent>,
+    mut sprite_query: Query<(Entity, &mut Animator, &mut TextureAtlas)>
+) {
+    // the picture freezes along with everything else
+    if hit_stop_timer.frozen {
+        return;
+    }
+
+    for (entity, mut animator, mut atlas) in sprite_query.iter_mut() {
+        let Some(animation) = player_animations.set.animations.get(&animator.state) else {
+            continue;
+        };
+        if animation.frames.is_empty() {
+            continue;
+        }
+
+        let mut send_event = |frame: &FrameData| {
+            if let Some(name) = &frame.event {
+                animation_event_writer.send(AnimationEvent { entity, name: name.clone() });
+            }
+        };
+
+        if !animator.started {
+            animator.started = true;
+            send_event(&animation.frames[0]);
+        }
+
+        animator.elapsed += time.delta_seconds();
+        // a long frame can skip more than one, zero length frames would never end
+        while
+            !animator.finished &&
+            animator.elapsed >= animation.frames[animator.frame].duration.max(0.01)
+        {
+            animator.elapsed -= animation.frames[animator.frame].duration.max(0.01);
+
+            if animator.frame + 1 < animation.frames.len() {
+                animator.frame += 1;
+            } else if animation.looping {
+                animator.frame = 0;
+            } else {
+                animator.finished = true;
+                break;
+            }
+            send_event(&animation.frames[animator.frame]);
+        }
+
+        atlas.index = animation.frames[animator.frame].index;
+    }
+}
@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+
+// -- COMPONENTS --
+
+#[derive(Component)]
+pub struct Boss {
+    pub half_size: Vec2,
+    pub home: Vec2,
+    pub health: u32,
+    // what running into it does to the player
+    pub damage: u32,
+    pub fighting: bool,
+    // index into the level's phases
+    pub phase: usize,
+    // index into the phase's attacks, they loop round once they're all done
+    pub attack: usize,
+    // seconds into the current attack
+    pub elapsed: f32,
+    // where it was when the current attack started
+    pub from: Vec2,
+    // seconds before it can be hurt again
+    pub invulnerable: f32,
+    // volleys fired so far, spirals turn a bit further each one
+    pub volleys: u32,
+}
+
+// wall that's only there while the fight's on, so the player can't leave
+#[derive(Component)]
+pub struct ArenaDoor {}
+
+// platform the boss's attacks can move around
+#[derive(Component)]
+pub struct ArenaPlatform {
+    // index into the boss's platforms, what MovePlatform refers to it by
+    pub index: usize,
+    pub home: Vec2,
+    pub from: Vec2,
+    pub to: Vec2,
+    // seconds the move takes
+    pub time: f32,
+    pub elapsed: f32,
+}
+
+#[derive(Component)]
+pub struct BossHealthBar {}
+
+#[derive(Component)]
+pub struct BossHealthFill {}
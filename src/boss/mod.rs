@@ -0,0 +1,65 @@
+// import bevy crates
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::PhysicsSet;
+
+// import modules
+pub mod components;
+pub mod resources;
+pub mod systems;
+
+// use modules
+use resources::*;
+use systems::*;
+use crate::level::systems::spawn_current_level;
+use crate::{ player_movement, simulation_running, AppState, SimulationState };
+
+pub const BOSS_COLOR: Color = Color::MAROON;
+pub const ARENA_DOOR_COLOR: Color = Color::DARK_GRAY;
+pub const ARENA_PLATFORM_COLOR: Color = Color::SILVER;
+pub const BOSS_HEALTH_BAR_COLOR: Color = Color::CRIMSON;
+pub const BOSS_HEALTH_BAR_BACKGROUND_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.6);
+pub const BOSS_HEALTH_BAR_WIDTH: f32 = 480.0;
+// seconds after a hit before another one counts
+pub const BOSS_INVULNERABLE_TIME: f32 = 0.8;
+// seconds each blink lasts while it can't be hurt
+pub const BOSS_FLASH_INTERVAL: f32 = 0.08;
+pub const BOSS_HIT_TRAUMA: f32 = 0.35;
+pub const BOSS_HIT_STOP_TICKS: u32 = 6;
+pub const BOSS_DEFEAT_TRAUMA: f32 = 0.7;
+// pixels a second it drops at when slamming
+pub const SLAM_SPEED: f32 = 1200.0;
+pub const SLAM_TRAUMA: f32 = 0.4;
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LevelBoss>()
+            .add_event::<BossDefeated>()
+            .add_systems(
+                OnEnter(AppState::Game),
+                (spawn_boss, spawn_boss_hud).after(spawn_current_level)
+            )
+            // before the player moves, same as enemies, so a stomp bounces them on the same tick
+            .add_systems(
+                FixedUpdate,
+                (
+                    reset_boss_fight,
+                    start_boss_fight,
+                    run_boss_attacks,
+                    move_arena_platforms,
+                    player_touches_boss,
+                )
+                    .chain()
+                    .before(player_movement)
+                    .before(PhysicsSet::SyncBackend)
+                    .run_if(simulation_running)
+            )
+            .add_systems(
+                Update,
+                (flash_boss, update_boss_hud).run_if(
+                    in_state(AppState::Game).and_then(in_state(SimulationState::Running))
+                )
+            );
+    }
+}
@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::camera::resources::CameraZoneData;
+use crate::level::resources::PlatformData;
+use crate::turret::resources::ProjectilePattern;
+use crate::default_damage;
+
+// -- EVENTS --
+
+// the level's boss has run out of health
+#[derive(Event)]
+pub struct BossDefeated {}
+
+// -- LEVEL DATA --
+
+// the current level's boss if it has one, spawned once the rest of the level's in
+#[derive(Resource, Default)]
+pub struct LevelBoss {
+    pub boss: Option<BossData>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct BossData {
+    // shown over its health bar
+    pub name: String,
+    // center
+    pub position: (f32, f32),
+    pub size: (f32, f32),
+    pub health: u32,
+    // taken off the player's health when they run into it from anywhere but above
+    #[serde(default = "default_damage")]
+    pub damage: u32,
+    // walking into this starts the fight, and the camera's held in it till it's over
+    pub arena: CameraZoneData,
+    // walls that close behind the player when the fight starts
+    #[serde(default)]
+    pub doors: Vec<PlatformData>,
+    // platforms the boss can move with MovePlatform, referred to by their index here
+    #[serde(default)]
+    pub platforms: Vec<PlatformData>,
+    // each takes over once the boss is down to its health
+    pub phases: Vec<BossPhaseData>,
+}
+
+impl BossData {
+    // the phase with the lowest health it's got down to, the first one till it's
+    // down to any of them
+    pub fn phase_at(&self, health: u32) -> usize {
+        self.phases
+            .iter()
+            .enumerate()
+            .filter(|(_, phase)| health <= phase.health)
+            .min_by_key(|(_, phase)| phase.health)
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct BossPhaseData {
+    pub health: u32,
+    // played in order, then from the top again
+    pub attacks: Vec<BossAttack>,
+}
+
+// positions are centers, times are seconds
+#[derive(Deserialize, Clone, Copy)]
+pub enum BossAttack {
+    // does nothing for a bit
+    Wait(f32),
+    // one volley, angles and all work the same as a turret's
+    Fire {
+        pattern: ProjectilePattern,
+        #[serde(default = "default_projectile_speed")]
+        speed: f32,
+        #[serde(default = "default_damage")]
+        damage: u32,
+    },
+    // glides over to the position
+    MoveTo {
+        position: (f32, f32),
+        time: f32,
+    },
+    // sets one of the arena's platforms moving, doesn't wait for it to get there
+    MovePlatform {
+        platform: usize,
+        position: (f32, f32),
+        time: f32,
+    },
+    // drops straight down till it lands on something
+    Slam,
+}
+
+fn default_projectile_speed() -> f32 {
+    300.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a boss with 10 health and a phase for each of the given thresholds, in that order
+    fn boss(thresholds: &[u32]) -> BossData {
+        let phases: Vec<String> = thresholds
+            .iter()
+            .map(|health| format!("(health: {}, attacks: [Wait(1.0)])", health))
+            .collect();
+
+        ron::from_str(
+            &format!(
+                "(
+                    name: \"Boss\",
+                    position: (0.0, 0.0),
+                    size: (100.0, 100.0),
+                    health: 10,
+                    arena: (size: (800.0, 600.0), position: (0.0, 0.0)),
+                    phases: [{}],
+                )",
+                phases.join(", ")
+            )
+        ).unwrap()
+    }
+
+    #[test]
+    fn each_phase_starts_at_its_threshold() {
+        let boss = boss(&[10, 6, 3]);
+
+        assert_eq!(boss.phase_at(10), 0);
+        assert_eq!(boss.phase_at(7), 0);
+        assert_eq!(boss.phase_at(6), 1);
+        assert_eq!(boss.phase_at(3), 2);
+        assert_eq!(boss.phase_at(0), 2);
+    }
+
+    #[test]
+    fn above_the_first_threshold_is_still_the_first_phase() {
+        let boss = boss(&[8, 4]);
+
+        assert_eq!(boss.phase_at(10), 0);
+        assert_eq!(boss.phase_at(9), 0);
+        assert_eq!(boss.phase_at(8), 0);
+        assert_eq!(boss.phase_at(4), 1);
+    }
+
+    #[test]
+    fn phases_out_of_order_still_go_by_health() {
+        let boss = boss(&[3, 10, 6]);
+
+        assert_eq!(boss.phase_at(10), 1);
+        assert_eq!(boss.phase_at(6), 2);
+        assert_eq!(boss.phase_at(4), 2);
+        assert_eq!(boss.phase_at(3), 0);
+        assert_eq!(boss.phase_at(0), 0);
+    }
+}
@@ -0,0 +1,566 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::boss::components::*;
+use crate::boss::resources::*;
+use crate::boss::{
+    ARENA_DOOR_COLOR,
+    ARENA_PLATFORM_COLOR,
+    BOSS_COLOR,
+    BOSS_DEFEAT_TRAUMA,
+    BOSS_FLASH_INTERVAL,
+    BOSS_HEALTH_BAR_BACKGROUND_COLOR,
+    BOSS_HEALTH_BAR_COLOR,
+    BOSS_HEALTH_BAR_WIDTH,
+    BOSS_HIT_STOP_TICKS,
+    BOSS_HIT_TRAUMA,
+    BOSS_INVULNERABLE_TIME,
+    SLAM_SPEED,
+    SLAM_TRAUMA,
+};
+use crate::camera::resources::CameraLock;
+use crate::enemy::STOMP_TOLERANCE;
+use crate::interpolation::components::{ Interpolated, InterpolatedVisual };
+use crate::juice::resources::{ HitStop, ScreenShake };
+use crate::level::components::LevelEntity;
+use crate::particles::resources::SpawnParticles;
+use crate::sound::resources::{ PlaySfx, SfxId };
+use crate::turret::components::Projectile;
+use crate::turret::systems::spawn_projectile;
+use crate::turret::MAX_PROJECTILES;
+use crate::{
+    bounce_player,
+    Dash,
+    JumpTimer,
+    Jumps,
+    Player,
+    PlayerDamaged,
+    PlayerDied,
+    HALF_PLAYER,
+};
+
+pub fn spawn_boss(
+    mut commands: Commands,
+    level_boss: Res<LevelBoss>,
+    mut camera_lock: ResMut<CameraLock>
+) {
+    // a fight that was on when the last level was left shouldn't hold the camera in this one
+    camera_lock.zone = None;
+
+    let Some(boss) = level_boss.boss.as_ref() else {
+        return;
+    };
+    let position = Vec2::new(boss.position.0, boss.position.1);
+    let half_size = Vec2::new(boss.size.0, boss.size.1) / 2.0;
+
+    commands
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_translation(position.extend(0.0))),
+            Interpolated::new(position),
+            Boss {
+                half_size,
+                home: position,
+                health: boss.health,
+                damage: boss.damage,
+                fighting: false,
+                phase: 0,
+                attack: 0,
+                elapsed: 0.0,
+                from: position,
+                invulnerable: 0.0,
+                volleys: 0,
+            },
+            RigidBody::KinematicPositionBased,
+            Collider::cuboid(half_size.x, half_size.y),
+            Sensor,
+            LevelEntity {},
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: BOSS_COLOR,
+                        custom_size: Some(half_size * 2.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                InterpolatedVisual {},
+            ));
+        });
+
+    // left open till the fight starts
+    for door in boss.doors.iter() {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: ARENA_DOOR_COLOR,
+                    custom_size: Some(Vec2::new(door.size.0, door.size.1)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(door.position.0, door.position.1, 0.0),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            RigidBody::Fixed,
+            Collider::cuboid(door.size.0 / 2.0, door.size.1 / 2.0),
+            ColliderDisabled,
+            ArenaDoor {},
+            LevelEntity {},
+        ));
+    }
+
+    for (index, platform) in boss.platforms.iter().enumerate() {
+        let position = Vec2::new(platform.position.0, platform.position.1);
+        let size = Vec2::new(platform.size.0, platform.size.1);
+
+        commands
+            .spawn((
+                SpatialBundle::from_transform(Transform::from_translation(position.extend(0.0))),
+                Interpolated::new(position),
+                ArenaPlatform {
+                    index,
+                    home: position,
+                    from: position,
+                    to: position,
+                    time: 0.0,
+                    elapsed: 0.0,
+                },
+                // moved by the script every tick, so rapier has to know it's meant to move
+                RigidBody::KinematicPositionBased,
+                Collider::cuboid(size.x / 2.0, size.y / 2.0),
+                LevelEntity {},
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: ARENA_PLATFORM_COLOR,
+                            custom_size: Some(size),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    InterpolatedVisual {},
+                ));
+            });
+    }
+}
+
+// dying mid-fight puts everything back the way it was before the player walked in
+pub fn reset_boss_fight(
+    mut commands: Commands,
+    mut player_died_event_reader: EventReader<PlayerDied>,
+    mut boss_query: Query<(Entity, &mut Transform, &mut Interpolated, &mut Boss)>,
+    mut platform_query: Query<
+        (&mut Transform, &mut Interpolated, &mut ArenaPlatform),
+        Without<Boss>
+    >,
+    mut door_query: Query<(Entity, &mut Visibility), With<ArenaDoor>>,
+    projectile_query: Query<(Entity, &Projectile)>,
+    mut camera_lock: ResMut<CameraLock>,
+    level_boss: Res<LevelBoss>
+) {
+    if player_died_event_reader.read().count() == 0 {
+        return;
+    }
+    let Some(data) = level_boss.boss.as_ref() else {
+        return;
+    };
+
+    for (entity, mut transform, mut interpolated, mut boss) in boss_query.iter_mut() {
+        if !boss.fighting {
+            continue;
+        }
+
+        // anything it fired that's still flying would be waiting for the player at the spawn
+        for (projectile_entity, projectile) in projectile_query.iter() {
+            if projectile.source == entity {
+                commands.entity(projectile_entity).despawn_recursive();
+            }
+        }
+
+        transform.translation.x = boss.home.x;
+        transform.translation.y = boss.home.y;
+        interpolated.snap_to(boss.home);
+        *boss = Boss {
+            health: data.health,
+            fighting: false,
+            phase: 0,
+            attack: 0,
+            elapsed: 0.0,
+            from: boss.home,
+            invulnerable: 0.0,
+            volleys: 0,
+            ..*boss
+        };
+
+        for (mut transform, mut interpolated, mut platform) in platform_query.iter_mut() {
+            transform.translation.x = platform.home.x;
+            transform.translation.y = platform.home.y;
+            interpolated.snap_to(platform.home);
+            platform.from = platform.home;
+            platform.to = platform.home;
+            // otherwise a move cut off by the reset finishes its old timing from home
+            platform.time = 0.0;
+            platform.elapsed = 0.0;
+        }
+        set_doors_closed(&mut commands, &mut door_query, false);
+        camera_lock.zone = None;
+    }
+}
+
+pub fn start_boss_fight(
+    mut commands: Commands,
+    level_boss: Res<LevelBoss>,
+    player_query: Query<&Transform, With<Player>>,
+    mut boss_query: Query<(&Transform, &mut Boss), Without<Player>>,
+    mut door_query: Query<(Entity, &mut Visibility), With<ArenaDoor>>,
+    mut camera_lock: ResMut<CameraLock>
+) {
+    let Some(data) = level_boss.boss.as_ref() else {
+        return;
+    };
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    for (transform, mut boss) in boss_query.iter_mut() {
+        if boss.fighting || !data.arena.contains(player_transform.translation.truncate()) {
+            continue;
+        }
+
+        boss.fighting = true;
+        boss.phase = data.phase_at(boss.health);
+        boss.attack = 0;
+        boss.elapsed = 0.0;
+        boss.from = transform.translation.truncate();
+        set_doors_closed(&mut commands, &mut door_query, true);
+        camera_lock.zone = Some(data.arena.clone());
+    }
+}
+
+// steps through the current phase's attacks, one at a time
+pub fn run_boss_attacks(
+    mut commands: Commands,
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    level_boss: Res<LevelBoss>,
+    player_query: Query<&Transform, With<Player>>,
+    mut boss_query: Query<(Entity, &mut Transform, &mut Boss), Without<Player>>,
+    mut platform_query: Query<(&Transform, &mut ArenaPlatform), Without<Boss>>,
+    projectile_query: Query<(), With<Projectile>>,
+    mut screen_shake_event_writer: EventWriter<ScreenShake>,
+    mut play_sfx_event_writer: EventWriter<PlaySfx>,
+    mut spawn_particles_event_writer: EventWriter<SpawnParticles>
+) {
+    let Some(data) = level_boss.boss.as_ref() else {
+        return;
+    };
+    let player = player_query
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation.truncate());
+    let mut live = projectile_query.iter().count();
+
+    for (entity, mut transform, mut boss) in boss_query.iter_mut() {
+        if !boss.fighting {
+            continue;
+        }
+        let position = transform.translation.truncate();
+
+        // getting knocked into the next phase starts its attacks from the top
+        let phase = data.phase_at(boss.health);
+        if phase != boss.phase {
+            boss.phase = phase;
+            boss.attack = 0;
+            boss.elapsed = 0.0;
+            boss.from = position;
+        }
+        let Some(attacks) = data.phases.get(phase).map(|phase| &phase.attacks) else {
+            continue;
+        };
+        let Some(attack) = attacks.get(boss.attack) else {
+            continue;
+        };
+        boss.elapsed += time.delta_seconds();
+
+        let done = match *attack {
+            BossAttack::Wait(duration) => boss.elapsed >= duration,
+            BossAttack::Fire { pattern, speed, damage } => {
+                let toward_player = player.and_then(|player| (player - position).try_normalize());
+                for direction in pattern.directions(boss.volleys, toward_player) {
+                    if live >= MAX_PROJECTILES {
+                        break;
+                    }
+                    live += 1;
+                    spawn_projectile(&mut commands, position, direction * speed, damage, entity);
+                }
+                boss.volleys += 1;
+                true
+            }
+            BossAttack::MoveTo { position: (x, y), time: duration } => {
+                let t = if duration > 0.0 { (boss.elapsed / duration).min(1.0) } else { 1.0 };
+                let moved = boss.from.lerp(Vec2::new(x, y), t);
+                transform.translation.x = moved.x;
+                transform.translation.y = moved.y;
+                t >= 1.0
+            }
+            BossAttack::MovePlatform { platform, position: (x, y), time: duration } => {
+                for (platform_transform, mut arena_platform) in platform_query.iter_mut() {
+                    if arena_platform.index == platform {
+                        arena_platform.from = platform_transform.translation.truncate();
+                        arena_platform.to = Vec2::new(x, y);
+                        arena_platform.time = duration;
+                        arena_platform.elapsed = 0.0;
+                    }
+                }
+                true
+            }
+            BossAttack::Slam => {
+                // never goes below the bottom of the arena, so the slam always finishes
+                // even with nothing underneath
+                let lowest = data.arena.position.1 - data.arena.size.1 / 2.0 +
+                    boss.half_size.y;
+                let fall = (SLAM_SPEED * time.delta_seconds()).min(
+                    (position.y - lowest).max(0.0)
+                );
+                let landing = rapier_context
+                    .cast_shape(
+                        position,
+                        0.0,
+                        Vec2::NEG_Y,
+                        &Collider::cuboid(boss.half_size.x, boss.half_size.y),
+                        fall,
+                        true,
+                        // the arena platforms are kinematic, so fixed alone isn't enough
+                        QueryFilter::exclude_dynamic().exclude_sensors()
+                    )
+                    .map(|(_, toi)| toi.toi);
+                transform.translation.y -= landing.unwrap_or(fall);
+
+                let landed = landing.is_some() || transform.translation.y <= lowest;
+                if landed {
+                    screen_shake_event_writer.send(ScreenShake { trauma: SLAM_TRAUMA });
+                    play_sfx_event_writer.send(PlaySfx(SfxId::Land));
+                    spawn_particles_event_writer.send(SpawnParticles {
+                        effect: "landing_puff",
+                        position: Vec2::new(
+                            position.x,
+                            transform.translation.y - boss.half_size.y
+                        ),
+                        source: None,
+                    });
+                }
+                landed
+            }
+        };
+
+        if done {
+            boss.attack = (boss.attack + 1) % attacks.len();
+            boss.elapsed = 0.0;
+            boss.from = transform.translation.truncate();
+        }
+    }
+}
+
+pub fn move_arena_platforms(
+    time: Res<Time>,
+    mut platform_query: Query<(&mut Transform, &mut ArenaPlatform)>
+) {
+    for (mut transform, mut platform) in platform_query.iter_mut() {
+        if platform.elapsed >= platform.time {
+            continue;
+        }
+        platform.elapsed += time.delta_seconds();
+        let t = (platform.elapsed / platform.time).min(1.0);
+        let moved = platform.from.lerp(platform.to, t);
+        transform.translation.x = moved.x;
+        transform.translation.y = moved.y;
+    }
+}
+
+// coming down on top hurts the boss and bounces the player, anything else hurts them
+pub fn player_touches_boss(
+    mut commands: Commands,
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    mut player_query: Query<(Entity, &Interpolated, &mut Jumps, &mut Dash), With<Player>>,
+    mut boss_query: Query<(Entity, &Transform, &mut Boss)>,
+    mut door_query: Query<(Entity, &mut Visibility), With<ArenaDoor>>,
+    mut camera_lock: ResMut<CameraLock>,
+    mut jump_timer: ResMut<JumpTimer>,
+    mut boss_defeated_event_writer: EventWriter<BossDefeated>,
+    mut player_damaged_event_writer: EventWriter<PlayerDamaged>,
+    mut screen_shake_event_writer: EventWriter<ScreenShake>,
+    mut hit_stop_event_writer: EventWriter<HitStop>,
+    mut play_sfx_event_writer: EventWriter<PlaySfx>,
+    mut spawn_particles_event_writer: EventWriter<SpawnParticles>
+) {
+    let Ok((player, interpolated, mut jumps, mut dash)) = player_query.get_single_mut() else {
+        return;
+    };
+    // where they were before the move that ran them into it
+    let feet_before = interpolated.previous.y - HALF_PLAYER;
+    let coming_down = interpolated.current.y <= interpolated.previous.y;
+
+    for (entity, transform, mut boss) in boss_query.iter_mut() {
+        boss.invulnerable = (boss.invulnerable - time.delta_seconds()).max(0.0);
+        if !boss.fighting || rapier_context.intersection_pair(player, entity) != Some(true) {
+            continue;
+        }
+
+        let top = transform.translation.y + boss.half_size.y;
+        if !(coming_down && feet_before >= top - STOMP_TOLERANCE) {
+            player_damaged_event_writer.send(PlayerDamaged {
+                damage: boss.damage,
+                source: transform.translation.truncate(),
+            });
+            continue;
+        }
+
+        // bounces off whether or not the hit counts
+        bounce_player(&mut jumps, &mut dash, &mut jump_timer);
+        play_sfx_event_writer.send(PlaySfx(SfxId::Stomp));
+        if boss.invulnerable > 0.0 {
+            continue;
+        }
+
+        boss.health = boss.health.saturating_sub(1);
+        boss.invulnerable = BOSS_INVULNERABLE_TIME;
+        screen_shake_event_writer.send(ScreenShake { trauma: BOSS_HIT_TRAUMA });
+        hit_stop_event_writer.send(HitStop { ticks: BOSS_HIT_STOP_TICKS });
+        spawn_particles_event_writer.send(SpawnParticles {
+            effect: "landing_puff",
+            position: Vec2::new(transform.translation.x, top),
+            source: None,
+        });
+
+        if boss.health == 0 {
+            commands.entity(entity).despawn_recursive();
+            set_doors_closed(&mut commands, &mut door_query, false);
+            camera_lock.zone = None;
+            screen_shake_event_writer.send(ScreenShake { trauma: BOSS_DEFEAT_TRAUMA });
+            spawn_particles_event_writer.send(SpawnParticles {
+                effect: "death_burst",
+                position: transform.translation.truncate(),
+                source: None,
+            });
+            boss_defeated_event_writer.send(BossDefeated {});
+        }
+    }
+}
+
+fn set_doors_closed(
+    commands: &mut Commands,
+    door_query: &mut Query<(Entity, &mut Visibility), With<ArenaDoor>>,
+    closed: bool
+) {
+    for (entity, mut visibility) in door_query.iter_mut() {
+        if closed {
+            commands.entity(entity).remove::<ColliderDisabled>();
+            *visibility = Visibility::Inherited;
+        } else {
+            commands.entity(entity).insert(ColliderDisabled);
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+// blinks while it can't be hurt so it's clear the hit landed
+pub fn flash_boss(
+    boss_query: Query<(&Boss, &Children)>,
+    mut visibility_query: Query<&mut Visibility, With<InterpolatedVisual>>
+) {
+    for (boss, children) in boss_query.iter() {
+        let hidden = boss.invulnerable > 0.0 &&
+            ((boss.invulnerable / BOSS_FLASH_INTERVAL) as u32) % 2 == 1;
+
+        for &child in children.iter() {
+            if let Ok(mut visibility) = visibility_query.get_mut(child) {
+                *visibility = if hidden { Visibility::Hidden } else { Visibility::Inherited };
+            }
+        }
+    }
+}
+
+// -- HUD --
+
+pub fn spawn_boss_hud(mut commands: Commands, level_boss: Res<LevelBoss>) {
+    let Some(boss) = level_boss.boss.as_ref() else {
+        return;
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(24.0),
+                    width: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(6.0),
+                    ..default()
+                },
+                // shown once the fight starts
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            BossHealthBar {},
+            LevelEntity {},
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(boss.name.clone(), TextStyle {
+                    font_size: 24.0,
+                    ..default()
+                })
+            );
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(BOSS_HEALTH_BAR_WIDTH),
+                        height: Val::Px(14.0),
+                        ..default()
+                    },
+                    background_color: BOSS_HEALTH_BAR_BACKGROUND_COLOR.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: BOSS_HEALTH_BAR_COLOR.into(),
+                            ..default()
+                        },
+                        BossHealthFill {},
+                    ));
+                });
+        });
+}
+
+pub fn update_boss_hud(
+    level_boss: Res<LevelBoss>,
+    boss_query: Query<&Boss>,
+    mut bar_query: Query<&mut Visibility, With<BossHealthBar>>,
+    mut fill_query: Query<&mut Style, With<BossHealthFill>>
+) {
+    let Some(data) = level_boss.boss.as_ref() else {
+        return;
+    };
+    // gone once it's beaten, so the bar goes with it
+    let boss = boss_query.iter().find(|boss| boss.fighting);
+
+    for mut visibility in bar_query.iter_mut() {
+        *visibility = if boss.is_some() { Visibility::Inherited } else { Visibility::Hidden };
+    }
+    if let (Some(boss), Ok(mut style)) = (boss, fill_query.get_single_mut()) {
+        let fraction = (boss.health as f32) / (data.health.max(1) as f32);
+        style.width = Val::Percent(fraction * 100.0);
+    }
+}
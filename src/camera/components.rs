@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+
+// -- COMPONENTS --
+
+// where the camera's trying to get to and how fast it's getting there
+#[derive(Component, Default)]
+pub struct CameraController {
+    // where the camera actually is before any shake gets added on top
+    pub position: Vec2,
+    // middle of the deadzone, only pushed along when the player reaches its edge
+    pub focus: Vec2,
+    pub velocity: Vec2,
+    // index into CameraZones of the room the camera's locked to, kept after the
+    // player leaves it until they walk into another one
+    pub zone: Option<usize>,
+    // held in CameraLock's room instead, the zone above gets picked back up after
+    pub locked: bool,
+    pub transition: Option<ZoneTransition>,
+}
+
+// sliding over to a room that's just been entered
+#[derive(Clone, Copy)]
+pub struct ZoneTransition {
+    pub from: Vec2,
+    pub from_zoom: f32,
+    pub elapsed: f32,
+}
@@ -0,0 +1,35 @@
+// import bevy crates
+use bevy::prelude::*;
+
+// import modules
+pub mod components;
+pub mod resources;
+pub mod systems;
+
+// use modules
+use resources::*;
+use systems::*;
+use crate::level::systems::spawn_current_level;
+use crate::{ AppState, SimulationState };
+
+// seconds the camera takes to slide from one room to the next
+pub const ZONE_TRANSITION_TIME: f32 = 0.4;
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        // levels swap in their own params when they load
+        app.init_resource::<CameraParams>()
+            .init_resource::<CameraZones>()
+            .init_resource::<CameraLock>()
+            .add_systems(Startup, spawn_camera)
+            .add_systems(OnEnter(AppState::Game), snap_camera.after(spawn_current_level))
+            .add_systems(
+                Update,
+                camera_follow.run_if(
+                    in_state(AppState::Game).and_then(in_state(SimulationState::Running))
+                )
+            );
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+// -- RESOURCES --
+
+// how the camera follows the player, set by each level's file. anything left out
+// gets the defaults below
+#[derive(Resource, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct CameraParams {
+    // full width/height of the box the player can move around in without the camera following
+    pub deadzone: (f32, f32),
+    // how far ahead of the player the camera sits in the direction they're facing
+    pub lookahead: f32,
+    // roughly how many seconds the camera takes to catch up, 0.0 snaps straight there
+    pub smooth_time: f32,
+    // only move up once the player lands, so jumps don't bob the camera around
+    pub platform_snap: bool,
+}
+
+// the rooms the camera locks to for the current level, none means it just follows
+#[derive(Resource, Default)]
+pub struct CameraZones {
+    pub zones: Vec<CameraZoneData>,
+}
+
+// a room the camera's held in whatever the player walks into, like a boss arena
+// while the fight's on
+#[derive(Resource, Default)]
+pub struct CameraLock {
+    pub zone: Option<CameraZoneData>,
+}
+
+impl CameraZones {
+    // the first room the point's inside, rooms listed earlier win where they overlap
+    pub fn zone_at(&self, point: Vec2) -> Option<usize> {
+        self.zones.iter().position(|zone| zone.contains(point))
+    }
+}
+
+impl Default for CameraParams {
+    fn default() -> CameraParams {
+        CameraParams {
+            deadzone: (120.0, 160.0),
+            lookahead: 120.0,
+            smooth_time: 0.25,
+            platform_snap: true,
+        }
+    }
+}
+
+// -- LEVEL DATA --
+
+// a room in a level file, size is full width/height and position is the center.
+// while the player's in it the camera stays inside it
+#[derive(Deserialize, Clone)]
+pub struct CameraZoneData {
+    pub size: (f32, f32),
+    pub position: (f32, f32),
+    #[serde(default)]
+    pub mode: CameraZoneMode,
+    // 1.0 is normal, bigger shows more of the room
+    #[serde(default)]
+    pub zoom: Option<f32>,
+    // follow params for inside this room, otherwise the level's
+    #[serde(default)]
+    pub camera: Option<CameraParams>,
+}
+
+#[derive(Deserialize, Clone, Copy, Default)]
+pub enum CameraZoneMode {
+    // follows the player but never shows anything outside the room
+    #[default]
+    Bounds,
+    // sits still at this point the whole time the player's in the room
+    Fixed(f32, f32),
+}
+
+impl CameraZoneData {
+    pub fn contains(&self, point: Vec2) -> bool {
+        (point.x - self.position.0).abs() <= self.size.0 / 2.0 &&
+            (point.y - self.position.1).abs() <= self.size.1 / 2.0
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom.unwrap_or(1.0)
+    }
+
+    // where the camera should be in this room when it'd otherwise go to goal.
+    // half_view is half the size of what the camera can see at this room's zoom
+    pub fn frame(&self, goal: Vec2, half_view: Vec2) -> Vec2 {
+        match self.mode {
+            CameraZoneMode::Fixed(x, y) => Vec2::new(x, y),
+            CameraZoneMode::Bounds =>
+                Vec2::new(
+                    clamp_to_room(goal.x, self.position.0, self.size.0 / 2.0, half_view.x),
+                    clamp_to_room(goal.y, self.position.1, self.size.1 / 2.0, half_view.y)
+                ),
+        }
+    }
+}
+
+// rooms smaller than the screen just get centered
+fn clamp_to_room(value: f32, center: f32, half_size: f32, half_view: f32) -> f32 {
+    if half_view >= half_size {
+        center
+    } else {
+        value.clamp(center - half_size + half_view, center + half_size - half_view)
+    }
+}
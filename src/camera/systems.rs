@@ -0,0 +1,221 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_rapier2d::prelude::*;
+
+use crate::camera::components::*;
+use crate::camera::resources::*;
+use crate::camera::ZONE_TRANSITION_TIME;
+use crate::interpolation::components::Interpolated;
+use crate::{ Direction, Player };
+
+pub fn spawn_camera(mut commands: Commands, window_query: Query<&Window, With<PrimaryWindow>>) {
+    let window: &Window = window_query.get_single().unwrap();
+    let center = Vec2::new(window.width() / 2.0, window.height() / 2.0);
+
+    commands.spawn((
+        Camera2dBundle {
+            transform: Transform::from_translation(center.extend(0.0)),
+            ..default()
+        },
+        CameraController {
+            position: center,
+            ..default()
+        },
+    ));
+}
+
+// jumps straight to the player when a level starts instead of swinging over from the last one
+pub fn snap_camera(
+    camera_params: Res<CameraParams>,
+    camera_zones: Res<CameraZones>,
+    direction: Res<Direction>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    player_query: Query<&Transform, With<Player>>,
+    mut camera_query: Query<
+        (&mut Transform, &mut OrthographicProjection, &mut CameraController),
+        Without<Player>
+    >
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let Ok((mut camera_transform, mut projection, mut controller)) = camera_query.get_single_mut()
+    else {
+        return;
+    };
+    let window: &Window = window_query.get_single().unwrap();
+
+    let player = player_transform.translation.truncate();
+    let zone_index = camera_zones.zone_at(player);
+    let zone = zone_index.map(|index| &camera_zones.zones[index]);
+    let params = zone.and_then(|zone| zone.camera).unwrap_or(*camera_params);
+    let (goal, zoom) = framed_goal(player, &params, *direction, zone, window);
+
+    *controller = CameraController {
+        position: goal,
+        focus: player,
+        velocity: Vec2::ZERO,
+        zone: zone_index,
+        locked: false,
+        transition: None,
+    };
+    camera_transform.translation.x = goal.x;
+    camera_transform.translation.y = goal.y;
+    projection.scale = zoom;
+}
+
+// follows where the player's drawn rather than where the last tick left them, inside
+// whichever room they're in
+pub fn camera_follow(
+    time: Res<Time>,
+    fixed_time: Res<Time<Fixed>>,
+    camera_params: Res<CameraParams>,
+    camera_zones: Res<CameraZones>,
+    camera_lock: Res<CameraLock>,
+    direction: Res<Direction>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    player_query: Query<(&Interpolated, Option<&KinematicCharacterControllerOutput>), With<Player>>,
+    mut camera_query: Query<
+        (&mut Transform, &mut OrthographicProjection, &mut CameraController),
+        Without<Player>
+    >
+) {
+    let Ok((interpolated, controller_output)) = player_query.get_single() else {
+        return;
+    };
+    let Ok((mut camera_transform, mut projection, mut controller)) = camera_query.get_single_mut()
+    else {
+        return;
+    };
+    let window: &Window = window_query.get_single().unwrap();
+    let controller = &mut *controller;
+
+    let player = interpolated.position(fixed_time.overstep_fraction());
+    let current = controller.position;
+
+    let new_zone = camera_zones.zone_at(player);
+    let locked = camera_lock.zone.is_some();
+    // walking into another room starts a slide over to it, and so does getting locked
+    // into or let out of one
+    let moved_zone = !locked && new_zone.is_some() && controller.zone != new_zone;
+    if moved_zone || controller.locked != locked {
+        if moved_zone {
+            controller.zone = new_zone;
+        }
+        controller.locked = locked;
+        controller.velocity = Vec2::ZERO;
+        controller.transition = Some(ZoneTransition {
+            from: current,
+            from_zoom: projection.scale,
+            elapsed: 0.0,
+        });
+    }
+
+    let zone = match camera_lock.zone.as_ref() {
+        Some(zone) => Some(zone),
+        None => controller.zone.map(|index| &camera_zones.zones[index]),
+    };
+    let params = zone.and_then(|zone| zone.camera).unwrap_or(*camera_params);
+    let half_deadzone = Vec2::new(params.deadzone.0, params.deadzone.1) / 2.0;
+    let grounded = controller_output.is_some_and(|output| output.grounded);
+
+    controller.focus.x = push_into_deadzone(controller.focus.x, player.x, half_deadzone.x);
+    controller.focus.y = if !params.platform_snap {
+        push_into_deadzone(controller.focus.y, player.y, half_deadzone.y)
+    } else if grounded {
+        player.y
+    } else {
+        // in the air only falls get followed, going up waits for the landing
+        controller.focus.y.min(player.y + half_deadzone.y)
+    };
+
+    let (goal, zoom) = framed_goal(controller.focus, &params, *direction, zone, window);
+
+    let (position, scale) = match controller.transition.as_mut() {
+        Some(transition) => {
+            transition.elapsed += time.delta_seconds();
+            let t = (transition.elapsed / ZONE_TRANSITION_TIME).min(1.0);
+            // eases in and out so the slide doesn't start or stop with a jolt
+            let eased = t * t * (3.0 - 2.0 * t);
+            let slid = (
+                transition.from.lerp(goal, eased),
+                transition.from_zoom + (zoom - transition.from_zoom) * eased,
+            );
+            if t >= 1.0 {
+                controller.transition = None;
+            }
+            slid
+        }
+        None => {
+            let smoothed = smooth_damp(
+                current,
+                goal,
+                &mut controller.velocity,
+                params.smooth_time,
+                time.delta_seconds()
+            );
+            (smoothed, zoom)
+        }
+    };
+
+    controller.position = position;
+    camera_transform.translation.x = position.x;
+    camera_transform.translation.y = position.y;
+    projection.scale = scale;
+}
+
+// where the camera's headed and how far it's zoomed out, kept inside the room if there is one
+fn framed_goal(
+    focus: Vec2,
+    camera_params: &CameraParams,
+    direction: Direction,
+    zone: Option<&CameraZoneData>,
+    window: &Window
+) -> (Vec2, f32) {
+    let goal = camera_goal(focus, camera_params, direction);
+
+    match zone {
+        Some(zone) => {
+            let half_view = (Vec2::new(window.width(), window.height()) / 2.0) * zone.zoom();
+            (zone.frame(goal, half_view), zone.zoom())
+        }
+        None => (goal, 1.0),
+    }
+}
+
+fn camera_goal(focus: Vec2, camera_params: &CameraParams, direction: Direction) -> Vec2 {
+    let facing = match direction {
+        Direction::Left => -1.0,
+        Direction::Right => 1.0,
+    };
+    focus + Vec2::new(facing * camera_params.lookahead, 0.0)
+}
+
+// moves focus just far enough that target is back inside focus +/- half_size
+fn push_into_deadzone(focus: f32, target: f32, half_size: f32) -> f32 {
+    focus.clamp(target - half_size, target + half_size)
+}
+
+// critically damped spring, gets to the goal as fast as it can without overshooting.
+// from Game Programming Gems 4, the same thing unity's SmoothDamp does
+fn smooth_damp(
+    current: Vec2,
+    goal: Vec2,
+    velocity: &mut Vec2,
+    smooth_time: f32,
+    delta_seconds: f32
+) -> Vec2 {
+    if smooth_time <= 0.0 {
+        *velocity = Vec2::ZERO;
+        return goal;
+    }
+
+    let omega = 2.0 / smooth_time;
+    let x = omega * delta_seconds;
+    let decay = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+    let change = current - goal;
+    let temp = (*velocity + omega * change) * delta_seconds;
+    *velocity = (*velocity - omega * temp) * decay;
+
+    goal + (change + temp) * decay
+}
@@ -0,0 +1,53 @@
+use std::f32::consts::FRAC_1_SQRT_2;
+
+// import bevy crates
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+
+// import modules
+pub mod resources;
+pub mod systems;
+
+// use modules
+use resources::*;
+use systems::*;
+use crate::juice::resources::HitStopTimer;
+
+// the 8 ways aim can snap to, going anticlockwise from right. written out rather
+// than worked out with sin/cos so straight directions come out exactly straight
+pub const AIM_DIRECTIONS: [Vec2; 8] = [
+    Vec2::new(1.0, 0.0),
+    Vec2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    Vec2::new(0.0, 1.0),
+    Vec2::new(-FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    Vec2::new(-1.0, 0.0),
+    Vec2::new(-FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+    Vec2::new(0.0, -1.0),
+    Vec2::new(FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+];
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        // actions are worked out once per frame before anything reads them
+        app.init_resource::<ActionState>()
+            .init_resource::<TickActions>()
+            .init_resource::<InputDevice>()
+            .add_systems(
+                PreUpdate,
+                (
+                    update_action_state,
+                    latch_actions.after(update_action_state),
+                    track_input_device,
+                ).after(InputSystem)
+            )
+            // frozen ticks leave presses waiting for the first tick after the hit-stop
+            .add_systems(
+                FixedPreUpdate,
+                update_tick_actions.run_if(|hit_stop_timer: Res<HitStopTimer>| {
+                    !hit_stop_timer.frozen
+                })
+            );
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::{ BTreeMap, HashSet };
+
+use bevy::prelude::*;
+use serde::{ Deserialize, Serialize };
+
+// -- ACTIONS --
+
+// everything the player can do, gameplay reads these instead of keys
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Up,
+    Down,
+    Jump,
+    Dash,
+    Pause,
+}
+
+// in the order the controls menu lists them
+pub const ACTIONS: [Action; 7] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::Up,
+    Action::Down,
+    Action::Jump,
+    Action::Dash,
+    Action::Pause,
+];
+
+impl Action {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Up => "Up",
+            Action::Down => "Down",
+            Action::Jump => "Jump",
+            Action::Dash => "Dash",
+            Action::Pause => "Pause",
+        }
+    }
+
+    // jump and pause double as the menus' confirm and back, and pause is the only way
+    // out of a level, so these two always keep at least one binding
+    pub fn is_required(&self) -> bool {
+        matches!(self, Action::Jump | Action::Pause)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(KeyCode),
+    GamepadButton(GamepadButtonType),
+    // one half of a stick axis, held once it's pushed past the deadzone
+    GamepadAxis(GamepadAxisType, AxisDirection),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AxisDirection {
+    Positive,
+    Negative,
+}
+
+impl Binding {
+    // short name for menus, KeyA -> A, ArrowLeft -> Left
+    pub fn name(&self) -> String {
+        match self {
+            Binding::Key(key) => {
+                let name = format!("{:?}", key);
+                name.strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .or_else(|| name.strip_prefix("Arrow"))
+                    .unwrap_or(&name)
+                    .to_string()
+            }
+            // xbox names, they're what most pads print on the buttons
+            Binding::GamepadButton(button) =>
+                match button {
+                    GamepadButtonType::South => "Pad A".to_string(),
+                    GamepadButtonType::East => "Pad B".to_string(),
+                    GamepadButtonType::West => "Pad X".to_string(),
+                    GamepadButtonType::North => "Pad Y".to_string(),
+                    GamepadButtonType::LeftTrigger => "LB".to_string(),
+                    GamepadButtonType::RightTrigger => "RB".to_string(),
+                    GamepadButtonType::LeftTrigger2 => "LT".to_string(),
+                    GamepadButtonType::RightTrigger2 => "RT".to_string(),
+                    GamepadButtonType::LeftThumb => "L3".to_string(),
+                    GamepadButtonType::RightThumb => "R3".to_string(),
+                    GamepadButtonType::DPadUp => "D-pad up".to_string(),
+                    GamepadButtonType::DPadDown => "D-pad down".to_string(),
+                    GamepadButtonType::DPadLeft => "D-pad left".to_string(),
+                    GamepadButtonType::DPadRight => "D-pad right".to_string(),
+                    GamepadButtonType::Other(index) => format!("Pad button {}", index),
+                    other => format!("{:?}", other),
+                }
+            Binding::GamepadAxis(axis, direction) => {
+                use AxisDirection::*;
+                use GamepadAxisType::*;
+
+                match (axis, direction) {
+                    (LeftStickX, Negative) => "Stick left".to_string(),
+                    (LeftStickX, Positive) => "Stick right".to_string(),
+                    (LeftStickY, Positive) => "Stick up".to_string(),
+                    (LeftStickY, Negative) => "Stick down".to_string(),
+                    (axis, Positive) => format!("{:?}+", axis),
+                    (axis, Negative) => format!("{:?}-", axis),
+                }
+            }
+        }
+    }
+}
+
+// which bindings trigger each action, saved in settings.ron
+#[derive(Serialize, Deserialize, Clone)]
+pub struct InputMap {
+    pub bindings: BTreeMap<Action, Vec<Binding>>,
+}
+
+impl Default for InputMap {
+    fn default() -> InputMap {
+        use AxisDirection::*;
+        use Binding::*;
+
+        let bindings = [
+            (
+                Action::MoveLeft,
+                vec![
+                    Key(KeyCode::ArrowLeft),
+                    Key(KeyCode::KeyA),
+                    GamepadButton(GamepadButtonType::DPadLeft),
+                    GamepadAxis(GamepadAxisType::LeftStickX, Negative),
+                ],
+            ),
+            (
+                Action::MoveRight,
+                vec![
+                    Key(KeyCode::ArrowRight),
+                    Key(KeyCode::KeyD),
+                    GamepadButton(GamepadButtonType::DPadRight),
+                    GamepadAxis(GamepadAxisType::LeftStickX, Positive),
+                ],
+            ),
+            (
+                Action::Up,
+                vec![
+                    Key(KeyCode::ArrowUp),
+                    Key(KeyCode::KeyW),
+                    GamepadButton(GamepadButtonType::DPadUp),
+                    GamepadAxis(GamepadAxisType::LeftStickY, Positive),
+                ],
+            ),
+            (
+                Action::Down,
+                vec![
+                    Key(KeyCode::ArrowDown),
+                    Key(KeyCode::KeyS),
+                    GamepadButton(GamepadButtonType::DPadDown),
+                    GamepadAxis(GamepadAxisType::LeftStickY, Negative),
+                ],
+            ),
+            (Action::Jump, vec![Key(KeyCode::Space), GamepadButton(GamepadButtonType::South)]),
+            (
+                Action::Dash,
+                vec![
+                    Key(KeyCode::ShiftLeft),
+                    Key(KeyCode::ShiftRight),
+                    GamepadButton(GamepadButtonType::West),
+                    GamepadButton(GamepadButtonType::RightTrigger),
+                ],
+            ),
+            (
+                Action::Pause,
+                vec![
+                    Key(KeyCode::Escape),
+                    Key(KeyCode::KeyP),
+                    GamepadButton(GamepadButtonType::Start),
+                ],
+            ),
+        ];
+
+        InputMap {
+            bindings: bindings.into_iter().collect(),
+        }
+    }
+}
+
+impl InputMap {
+    pub fn bindings_for(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map_or(&[], |bindings| bindings.as_slice())
+    }
+
+    // the action a binding is already used for, if any
+    pub fn action_for(&self, binding: Binding) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, bindings)| bindings.contains(&binding))
+            .map(|(action, _)| *action)
+    }
+
+    // adds a binding to an action, taking it away from whatever had it before
+    pub fn bind(&mut self, action: Action, binding: Binding) {
+        for bindings in self.bindings.values_mut() {
+            bindings.retain(|existing| *existing != binding);
+        }
+        self.bindings.entry(action).or_default().push(binding);
+    }
+
+    // true when the action is required and is down to its last binding
+    pub fn is_last_binding(&self, action: Action) -> bool {
+        action.is_required() && self.bindings_for(action).len() <= 1
+    }
+
+    pub fn clear(&mut self, action: Action) {
+        self.bindings.remove(&action);
+    }
+}
+
+// -- RESOURCES --
+
+// which actions are held this frame, worked out from InputMap in PreUpdate
+#[derive(Resource, Default)]
+pub struct ActionState {
+    pub pressed: HashSet<Action>,
+    pub just_pressed: HashSet<Action>,
+    pub just_released: HashSet<Action>,
+    // direction being held, snapped to 8 ways. zero when nothing's held
+    pub aim: Vec2,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    pub fn just_released(&self, action: Action) -> bool {
+        self.just_released.contains(&action)
+    }
+}
+
+// what gameplay sees on each simulation tick. a frame can run any number of ticks,
+// so presses and releases pile up here until the next tick picks them up, which
+// keeps a tap from getting lost or counted twice
+#[derive(Resource, Default)]
+pub struct TickActions {
+    pub actions: ActionState,
+    pub pending_pressed: HashSet<Action>,
+    pub pending_released: HashSet<Action>,
+}
+
+// whatever was used last, so on-screen prompts can name the right buttons
+#[derive(Resource, Default, Clone, Copy, PartialEq)]
+pub enum InputDevice {
+    #[default]
+    Keyboard,
+    Gamepad,
+}
+
+impl InputDevice {
+    pub fn prompt<'a>(&self, keyboard: &'a str, gamepad: &'a str) -> &'a str {
+        match self {
+            InputDevice::Keyboard => keyboard,
+            InputDevice::Gamepad => gamepad,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPACE: Binding = Binding::Key(KeyCode::Space);
+    const ESCAPE: Binding = Binding::Key(KeyCode::Escape);
+    const KEY_P: Binding = Binding::Key(KeyCode::KeyP);
+
+    // jump on space, pause on escape and p, nothing else bound
+    fn input_map() -> InputMap {
+        let mut input_map = InputMap { bindings: BTreeMap::new() };
+        input_map.bind(Action::Jump, SPACE);
+        input_map.bind(Action::Pause, ESCAPE);
+        input_map.bind(Action::Pause, KEY_P);
+        input_map
+    }
+
+    #[test]
+    fn bind_moves_a_binding_off_its_old_action() {
+        let mut input_map = input_map();
+        input_map.bind(Action::Dash, KEY_P);
+
+        assert_eq!(input_map.bindings_for(Action::Pause), &[ESCAPE]);
+        assert_eq!(input_map.bindings_for(Action::Dash), &[KEY_P]);
+        assert_eq!(input_map.action_for(KEY_P), Some(Action::Dash));
+    }
+
+    #[test]
+    fn binding_the_same_key_again_keeps_one_copy() {
+        let mut input_map = input_map();
+        input_map.bind(Action::Jump, SPACE);
+
+        assert_eq!(input_map.bindings_for(Action::Jump), &[SPACE]);
+    }
+
+    #[test]
+    fn stealing_a_required_actions_last_binding_is_caught() {
+        let mut input_map = input_map();
+        assert!(input_map.is_last_binding(Action::Jump));
+        assert!(!input_map.is_last_binding(Action::Pause));
+
+        // p moving to dash leaves pause down to escape
+        input_map.bind(Action::Dash, KEY_P);
+        assert!(input_map.is_last_binding(Action::Pause));
+
+        // anything that isn't required can lose everything
+        input_map.clear(Action::Dash);
+        assert!(!input_map.is_last_binding(Action::Dash));
+    }
+}
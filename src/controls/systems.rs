@@ -0,0 +1,170 @@
+use std::collections::HashSet;
+use std::f32::consts::FRAC_PI_4;
+
+use bevy::input::gamepad::GamepadConnectionEvent;
+use bevy::prelude::*;
+
+use crate::controls::resources::*;
+use crate::controls::AIM_DIRECTIONS;
+use crate::settings::resources::Settings;
+
+pub fn update_action_state(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    settings: Res<Settings>,
+    mut action_state: ResMut<ActionState>
+) {
+    let binding_pressed = |binding: &Binding| {
+        match binding {
+            Binding::Key(key) => keyboard_input.pressed(*key),
+            // any connected pad counts, there's only ever one player
+            Binding::GamepadButton(button_type) =>
+                gamepads.iter().any(|gamepad| {
+                    gamepad_buttons.pressed(GamepadButton::new(gamepad, *button_type))
+                }),
+            Binding::GamepadAxis(axis_type, direction) =>
+                gamepads.iter().any(|gamepad| {
+                    let value = gamepad_axes
+                        .get(GamepadAxis::new(gamepad, *axis_type))
+                        .unwrap_or(0.0);
+                    match direction {
+                        AxisDirection::Positive => value > settings.stick_deadzone,
+                        AxisDirection::Negative => value < -settings.stick_deadzone,
+                    }
+                }),
+        }
+    };
+
+    let pressed: HashSet<Action> = ACTIONS.iter()
+        .copied()
+        .filter(|action| settings.bindings.bindings_for(*action).iter().any(binding_pressed))
+        .collect();
+
+    // an action is only just pressed when none of its bindings were held last frame
+    action_state.just_pressed = pressed.difference(&action_state.pressed).copied().collect();
+    action_state.just_released = action_state.pressed.difference(&pressed).copied().collect();
+    action_state.pressed = pressed;
+
+    // snapped so dashes always go one of 8 ways
+    let stick = gamepads
+        .iter()
+        .map(|gamepad| {
+            let axis = |axis_type| {
+                gamepad_axes.get(GamepadAxis::new(gamepad, axis_type)).unwrap_or(0.0)
+            };
+            Vec2::new(axis(GamepadAxisType::LeftStickX), axis(GamepadAxisType::LeftStickY))
+        })
+        .find(|stick| stick.length() > settings.stick_deadzone);
+
+    let held = |action: Action| if action_state.pressed(action) { 1.0 } else { 0.0 };
+    let aim = stick.unwrap_or_else(|| {
+        Vec2::new(
+            held(Action::MoveRight) - held(Action::MoveLeft),
+            held(Action::Up) - held(Action::Down)
+        )
+    });
+
+    action_state.aim = if aim == Vec2::ZERO { Vec2::ZERO } else { snap_to_8_way(aim) };
+}
+
+// holds on to this frame's presses and releases until a tick has seen them
+pub fn latch_actions(action_state: Res<ActionState>, mut tick_actions: ResMut<TickActions>) {
+    let tick_actions = &mut *tick_actions;
+    tick_actions.pending_pressed.extend(action_state.just_pressed.iter().copied());
+    tick_actions.pending_released.extend(action_state.just_released.iter().copied());
+}
+
+// a tap that was pressed and let go since the last tick still counts as held for this one
+pub fn update_tick_actions(
+    action_state: Res<ActionState>,
+    mut tick_actions: ResMut<TickActions>
+) {
+    let tick_actions = &mut *tick_actions;
+    let just_pressed = std::mem::take(&mut tick_actions.pending_pressed);
+    let just_released = std::mem::take(&mut tick_actions.pending_released);
+
+    tick_actions.actions.pressed = action_state.pressed.union(&just_pressed).copied().collect();
+    tick_actions.actions.just_pressed = just_pressed;
+    tick_actions.actions.just_released = just_released;
+    tick_actions.actions.aim = action_state.aim;
+}
+
+// switches prompts over when a pad is plugged in or pulled out, and whenever
+// the other device gets used
+pub fn track_input_device(
+    mut gamepad_connection_event_reader: EventReader<GamepadConnectionEvent>,
+    gamepads: Res<Gamepads>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut input_device: ResMut<InputDevice>
+) {
+    let mut device = *input_device;
+
+    for event in gamepad_connection_event_reader.read() {
+        if event.connected() {
+            device = InputDevice::Gamepad;
+        } else if gamepads.iter().next().is_none() {
+            device = InputDevice::Keyboard;
+        }
+    }
+
+    if keyboard_input.get_just_pressed().next().is_some() {
+        device = InputDevice::Keyboard;
+    } else if gamepad_buttons.get_just_pressed().next().is_some() {
+        device = InputDevice::Gamepad;
+    }
+
+    // only mark it changed when it actually is, prompts rebuild on change
+    input_device.set_if_neq(device);
+}
+
+fn snap_to_8_way(direction: Vec2) -> Vec2 {
+    let steps = (direction.y.atan2(direction.x) / FRAC_PI_4).round() as i32;
+    AIM_DIRECTIONS[steps.rem_euclid(8) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_1_SQRT_2;
+
+    fn assert_snaps(direction: Vec2, expected: Vec2) {
+        let snapped = snap_to_8_way(direction);
+        assert!(
+            snapped.distance(expected) < 1e-5,
+            "{:?} snapped to {:?}, not {:?}",
+            direction,
+            snapped,
+            expected
+        );
+    }
+
+    #[test]
+    fn straight_directions_stay_put() {
+        assert_snaps(Vec2::new(1.0, 0.0), Vec2::new(1.0, 0.0));
+        assert_snaps(Vec2::new(0.0, 0.5), Vec2::new(0.0, 1.0));
+        assert_snaps(Vec2::new(-2.0, 0.0), Vec2::new(-1.0, 0.0));
+        assert_snaps(Vec2::new(0.0, -1.0), Vec2::new(0.0, -1.0));
+    }
+
+    #[test]
+    fn diagonals_snap_to_unit_length() {
+        assert_snaps(Vec2::new(1.0, 1.0), Vec2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+        assert_snaps(Vec2::new(-0.3, 0.3), Vec2::new(-FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+        assert_snaps(Vec2::new(1.0, -0.9), Vec2::new(FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+    }
+
+    #[test]
+    fn each_side_of_the_halfway_angle_goes_to_the_nearer_direction() {
+        // 22.5 degrees is halfway between right and up-right
+        assert_snaps(Vec2::from_angle(20.0f32.to_radians()), Vec2::new(1.0, 0.0));
+        assert_snaps(
+            Vec2::from_angle(25.0f32.to_radians()),
+            Vec2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2)
+        );
+        // just under straight left, where atan2 flips from +180 to -180
+        assert_snaps(Vec2::new(-1.0, -0.01), Vec2::new(-1.0, 0.0));
+    }
+}
@@ -0,0 +1,90 @@
+use std::fs::{ self, File };
+use std::io::{ self, Write };
+use std::path::PathBuf;
+
+use bevy::asset::io::file::FileAssetReader;
+use ron::ser::PrettyConfig;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+// -- DATA FILES --
+
+// reads a .ron file out of the assets folder right away instead of going through the
+// asset server, for data that has to exist before anything gets spawned (levels, etc)
+pub fn load_ron<T: DeserializeOwned>(path: &str) -> Result<T, String> {
+    let full_path = FileAssetReader::get_base_path().join("assets").join(path);
+
+    let contents = fs::read_to_string(&full_path).map_err(|error| {
+        format!("couldn't read {}: {}", full_path.display(), error)
+    })?;
+
+    ron::from_str(&contents).map_err(|error| {
+        format!("couldn't parse {}: {}", full_path.display(), error)
+    })
+}
+
+// fnv-1a over the raw bits of some floats. the same numbers always hash the same
+// on every machine, and even the smallest change gives a different hash
+pub fn hash_floats(values: &[f32]) -> u64 {
+    values
+        .iter()
+        .flat_map(|value| value.to_bits().to_le_bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ (byte as u64)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+// -- SAVE FILES --
+
+pub const SAVE_FOLDER: &str = "gumperjame";
+
+// folder in the platform's data directory where progress gets written
+pub fn save_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(SAVE_FOLDER)
+}
+
+pub fn read_save_file(file_name: &str) -> Option<String> {
+    fs::read_to_string(save_dir().join(file_name)).ok()
+}
+
+pub fn save_file_exists(file_name: &str) -> bool {
+    save_dir().join(file_name).exists()
+}
+
+// writes to a temp file first and renames it over the real one, so a crash
+// halfway through a write leaves the old save untouched instead of half a file
+pub fn write_save_file(file_name: &str, contents: &str) -> io::Result<()> {
+    let path = save_dir().join(file_name);
+    // file_name can point into a subfolder, like replays/
+    if let Some(folder) = path.parent() {
+        fs::create_dir_all(folder)?;
+    }
+
+    let temp_path = save_dir().join(format!("{}.tmp", file_name));
+
+    let mut temp_file = File::create(&temp_path)?;
+    temp_file.write_all(contents.as_bytes())?;
+    temp_file.sync_all()?;
+
+    fs::rename(temp_path, path)
+}
+
+pub fn delete_save_file(file_name: &str) -> io::Result<()> {
+    fs::remove_file(save_dir().join(file_name))
+}
+
+pub fn rename_save_file(file_name: &str, new_file_name: &str) -> io::Result<()> {
+    fs::rename(save_dir().join(file_name), save_dir().join(new_file_name))
+}
+
+pub fn write_save<T: Serialize>(file_name: &str, value: &T) -> Result<(), String> {
+    let contents = ron::ser::to_string_pretty(value, PrettyConfig::default()).map_err(|error| {
+        format!("couldn't serialize {}: {}", file_name, error)
+    })?;
+
+    write_save_file(file_name, &contents).map_err(|error| {
+        format!("couldn't write {}: {}", file_name, error)
+    })
+}
@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+
+// -- COMPONENTS --
+
+// kills the player if they run into it, dies if they land on it. how it moves comes
+// from whichever behaviours below it's been given, in order of priority chase, return
+// home, then patrol, with hover bobbing on top of all of them
+#[derive(Component)]
+pub struct Enemy {
+    pub half_size: Vec2,
+    // where it was placed in the level
+    pub home: Vec2,
+    // fliers go straight wherever they're headed, everything else sticks to the ground
+    // it's on and won't walk off edges
+    pub flying: bool,
+    pub damage: u32,
+}
+
+// paces back and forth, turning at walls, edges and the end of its range
+#[derive(Component)]
+pub struct Patrol {
+    // pixels a second
+    pub speed: f32,
+    // 1.0 for right, -1.0 for left
+    pub direction: f32,
+    // how far either side of home it goes, as far as it can if none
+    pub range: Option<f32>,
+}
+
+// bobs up and down on a sine wave
+#[derive(Component)]
+pub struct Hover {
+    // pixels either side of the middle
+    pub amplitude: f32,
+    // seconds for one full bob
+    pub period: f32,
+    // seconds into the wave
+    pub elapsed: f32,
+}
+
+// goes for the player while they're close enough and there's nothing solid in the way
+#[derive(Component)]
+pub struct Chase {
+    pub radius: f32,
+    pub speed: f32,
+    // where the player is, while they can be seen
+    pub target: Option<Vec2>,
+}
+
+// heads back to where it started once there's nothing to chase
+#[derive(Component)]
+pub struct ReturnHome {
+    pub speed: f32,
+    pub returning: bool,
+}
@@ -0,0 +1,40 @@
+// import bevy crates
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::PhysicsSet;
+
+// import modules
+pub mod components;
+pub mod resources;
+pub mod systems;
+
+// use modules
+use resources::*;
+use systems::*;
+use crate::level::systems::spawn_current_level;
+use crate::{ player_movement, simulation_running, AppState };
+
+pub const ENEMY_COLOR: Color = Color::CRIMSON;
+// how far below a walker's feet it checks for floor before stepping off an edge
+pub const LEDGE_PROBE: f32 = 8.0;
+// how far down the side of an enemy the player's feet can be and still count as landing
+// on top, it's moved a whole tick by the time the overlap's noticed
+pub const STOMP_TOLERANCE: f32 = 12.0;
+pub const STOMP_TRAUMA: f32 = 0.2;
+pub const STOMP_HIT_STOP_TICKS: u32 = 4;
+
+pub struct EnemyPlugin;
+
+impl Plugin for EnemyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LevelEnemies>()
+            .add_systems(OnEnter(AppState::Game), spawn_enemies.after(spawn_current_level))
+            // before the player moves, so a stomp bounces them on the same tick
+            .add_systems(
+                FixedUpdate,
+                (chase_player, return_home, patrol, hover, player_touches_enemies).chain()
+                    .before(player_movement)
+                    .before(PhysicsSet::SyncBackend)
+                    .run_if(simulation_running)
+            );
+    }
+}
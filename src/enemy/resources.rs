@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::default_damage;
+
+// -- LEVEL DATA --
+
+// the current level's enemies, spawned once the rest of the level's in
+#[derive(Resource, Default)]
+pub struct LevelEnemies {
+    pub enemies: Vec<EnemyData>,
+}
+
+// enemies that don't fly keep to the height they're placed at, so they go standing on
+// a platform
+#[derive(Deserialize, Clone)]
+pub struct EnemyData {
+    // center
+    pub position: (f32, f32),
+    // full width/height
+    #[serde(default = "default_size")]
+    pub size: (f32, f32),
+    #[serde(default)]
+    pub flying: bool,
+    // taken off the player's health when they run into it from anywhere but above
+    #[serde(default = "default_damage")]
+    pub damage: u32,
+    // any mix of these, an enemy that's given none just patrols
+    #[serde(default = "default_behaviours")]
+    pub behaviours: Vec<BehaviourData>,
+}
+
+// speeds are pixels a second
+#[derive(Deserialize, Clone)]
+pub enum BehaviourData {
+    Patrol {
+        #[serde(default = "default_patrol_speed")]
+        speed: f32,
+        // sets off to the right unless this is set
+        #[serde(default)]
+        start_left: bool,
+        #[serde(default)]
+        range: Option<f32>,
+    },
+    Hover {
+        amplitude: f32,
+        period: f32,
+    },
+    Chase {
+        // how close the player has to get before it notices them
+        radius: f32,
+        speed: f32,
+    },
+    ReturnHome {
+        speed: f32,
+    },
+}
+
+fn default_size() -> (f32, f32) {
+    (40.0, 40.0)
+}
+
+fn default_behaviours() -> Vec<BehaviourData> {
+    vec![BehaviourData::Patrol {
+        speed: default_patrol_speed(),
+        start_left: false,
+        range: None,
+    }]
+}
+
+fn default_patrol_speed() -> f32 {
+    120.0
+}
@@ -0,0 +1,332 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::enemy::components::*;
+use crate::enemy::resources::*;
+use crate::enemy::{
+    ENEMY_COLOR,
+    LEDGE_PROBE,
+    STOMP_HIT_STOP_TICKS,
+    STOMP_TOLERANCE,
+    STOMP_TRAUMA,
+};
+use crate::interpolation::components::{ Interpolated, InterpolatedVisual };
+use crate::juice::resources::{ HitStop, ScreenShake };
+use crate::level::components::LevelEntity;
+use crate::particles::resources::SpawnParticles;
+use crate::sound::resources::{ PlaySfx, SfxId };
+use crate::{ bounce_player, Dash, JumpTimer, Jumps, Player, PlayerDamaged, HALF_PLAYER };
+
+pub fn spawn_enemies(mut commands: Commands, level_enemies: Res<LevelEnemies>) {
+    for enemy in level_enemies.enemies.iter() {
+        let position = Vec2::new(enemy.position.0, enemy.position.1);
+        let half_size = Vec2::new(enemy.size.0, enemy.size.1) / 2.0;
+
+        let mut entity = commands.spawn((
+            SpatialBundle::from_transform(Transform::from_translation(position.extend(0.0))),
+            Interpolated::new(position),
+            Enemy {
+                half_size,
+                home: position,
+                flying: enemy.flying,
+                damage: enemy.damage,
+            },
+            RigidBody::KinematicPositionBased,
+            Collider::cuboid(half_size.x, half_size.y),
+            Sensor,
+            LevelEntity {},
+        ));
+
+        for behaviour in enemy.behaviours.iter() {
+            match *behaviour {
+                BehaviourData::Patrol { speed, start_left, range } => {
+                    entity.insert(Patrol {
+                        speed,
+                        direction: if start_left { -1.0 } else { 1.0 },
+                        range,
+                    });
+                }
+                BehaviourData::Hover { amplitude, period } => {
+                    entity.insert(Hover {
+                        amplitude,
+                        period: period.max(0.01),
+                        elapsed: 0.0,
+                    });
+                }
+                BehaviourData::Chase { radius, speed } => {
+                    entity.insert(Chase {
+                        radius,
+                        speed,
+                        target: None,
+                    });
+                }
+                BehaviourData::ReturnHome { speed } => {
+                    entity.insert(ReturnHome {
+                        speed,
+                        returning: false,
+                    });
+                }
+            }
+        }
+
+        entity.with_children(|parent| {
+            parent.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: ENEMY_COLOR,
+                        custom_size: Some(half_size * 2.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                InterpolatedVisual {},
+            ));
+        });
+    }
+}
+
+// -- BEHAVIOURS --
+
+pub fn chase_player(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    player_query: Query<&Transform, With<Player>>,
+    mut chase_query: Query<(&mut Transform, &Enemy, &mut Chase), Without<Player>>
+) {
+    let player = player_query
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation.truncate());
+
+    for (mut transform, enemy, mut chase) in chase_query.iter_mut() {
+        let position = transform.translation.truncate();
+        chase.target = player.filter(|player| {
+            position.distance(*player) <= chase.radius &&
+                can_see(&rapier_context, position, *player)
+        });
+
+        if let Some(target) = chase.target {
+            let step = chase.speed * time.delta_seconds();
+            move_towards(&rapier_context, &mut transform, enemy, target, step);
+        }
+    }
+}
+
+pub fn return_home(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    mut return_query: Query<(&mut Transform, &Enemy, &mut ReturnHome, Option<&Chase>)>
+) {
+    for (mut transform, enemy, mut return_home, chase) in return_query.iter_mut() {
+        // anything it chases drags it away from home, so it'll have to come back after
+        if chase.is_some_and(|chase| chase.target.is_some()) {
+            return_home.returning = true;
+            continue;
+        }
+
+        if return_home.returning {
+            let away = if enemy.flying {
+                transform.translation.truncate().distance(enemy.home)
+            } else {
+                (transform.translation.x - enemy.home.x).abs()
+            };
+            let step = return_home.speed * time.delta_seconds();
+            let moved = move_towards(&rapier_context, &mut transform, enemy, enemy.home, step);
+            if away <= step || !moved {
+                return_home.returning = false;
+            }
+        }
+    }
+}
+
+pub fn patrol(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    mut patrol_query: Query<(Entity, &mut Transform, &Enemy, &mut Patrol)>,
+    chase_query: Query<&Chase>,
+    return_query: Query<&ReturnHome>
+) {
+    for (entity, mut transform, enemy, mut patrol) in patrol_query.iter_mut() {
+        let chasing = chase_query.get(entity).is_ok_and(|chase| chase.target.is_some());
+        let returning = return_query.get(entity).is_ok_and(|return_home| return_home.returning);
+        if chasing || returning {
+            continue;
+        }
+
+        let step = patrol.speed * time.delta_seconds();
+        let next_x = transform.translation.x + patrol.direction * step;
+        // only turns when heading further out, a chase could have left it anywhere
+        let out_of_range = patrol.range.is_some_and(|range| {
+            let from_home = (next_x - enemy.home.x).abs();
+            from_home > range && from_home > (transform.translation.x - enemy.home.x).abs()
+        });
+        let blocked = !can_step(&rapier_context, &transform, enemy, patrol.direction, step);
+
+        if out_of_range || blocked {
+            patrol.direction = -patrol.direction;
+        } else {
+            transform.translation.x = next_x;
+        }
+    }
+}
+
+// moves by how much the wave's changed since last tick, so it rides on top of whatever
+// else is moving the enemy
+pub fn hover(time: Res<Time>, mut hover_query: Query<(&mut Transform, &mut Hover)>) {
+    for (mut transform, mut hover) in hover_query.iter_mut() {
+        let before = wave(&hover);
+        hover.elapsed = (hover.elapsed + time.delta_seconds()) % hover.period;
+        transform.translation.y += wave(&hover) - before;
+    }
+}
+
+fn wave(hover: &Hover) -> f32 {
+    hover.amplitude * (TAU * hover.elapsed / hover.period).sin()
+}
+
+// heads straight for the target when flying, otherwise along the ground as far as it
+// safely can. false if it couldn't move at all
+fn move_towards(
+    rapier_context: &RapierContext,
+    transform: &mut Transform,
+    enemy: &Enemy,
+    target: Vec2,
+    step: f32
+) -> bool {
+    let position = transform.translation.truncate();
+
+    if enemy.flying {
+        let offset = target - position;
+        let distance = offset.length();
+        if distance <= f32::EPSILON {
+            return false;
+        }
+        let step = step.min(distance);
+        let direction = offset / distance;
+        // stops short of walls rather than flying through them
+        if let Some((_, toi)) = rapier_context.cast_shape(
+            position,
+            0.0,
+            direction,
+            &Collider::cuboid(enemy.half_size.x, enemy.half_size.y),
+            step,
+            true,
+            solid_ground()
+        ) {
+            let step = (toi.toi - 1.0).max(0.0);
+            transform.translation += (direction * step).extend(0.0);
+            return step > 0.0;
+        }
+        transform.translation += (direction * step).extend(0.0);
+        true
+    } else {
+        let offset = target.x - position.x;
+        if offset.abs() <= f32::EPSILON {
+            return false;
+        }
+        let direction = offset.signum();
+        let step = step.min(offset.abs());
+        if !can_step(rapier_context, transform, enemy, direction, step) {
+            return false;
+        }
+        transform.translation.x += direction * step;
+        true
+    }
+}
+
+// walls stop everything, and edges stop anything that doesn't fly
+fn can_step(
+    rapier_context: &RapierContext,
+    transform: &Transform,
+    enemy: &Enemy,
+    direction: f32,
+    step: f32
+) -> bool {
+    let position = transform.translation.truncate();
+    let ahead = Vec2::new(direction, 0.0);
+
+    let wall = rapier_context
+        .cast_ray(position, ahead, enemy.half_size.x + step, true, solid_ground())
+        .is_some();
+    if wall {
+        return false;
+    }
+    if enemy.flying {
+        return true;
+    }
+
+    // from just past its front foot, where it'd be standing after this step
+    let edge = position + ahead * (enemy.half_size.x + step) -
+        Vec2::new(0.0, enemy.half_size.y - 1.0);
+    rapier_context.cast_ray(edge, Vec2::NEG_Y, LEDGE_PROBE, true, solid_ground()).is_some()
+}
+
+// nothing solid between the two
+fn can_see(rapier_context: &RapierContext, from: Vec2, to: Vec2) -> bool {
+    let offset = to - from;
+    let distance = offset.length();
+    distance <= f32::EPSILON ||
+        rapier_context.cast_ray(from, offset / distance, distance, true, solid_ground()).is_none()
+}
+
+// only the level's solid ground, not spikes or other enemies or the player
+fn solid_ground() -> QueryFilter<'static> {
+    QueryFilter::only_fixed().exclude_sensors()
+}
+
+// coming down on top squashes the enemy and bounces the player, anything else hurts them
+pub fn player_touches_enemies(
+    mut commands: Commands,
+    rapier_context: Res<RapierContext>,
+    mut player_query: Query<(Entity, &Interpolated, &mut Jumps, &mut Dash), With<Player>>,
+    enemy_query: Query<(Entity, &Transform, &Enemy)>,
+    mut jump_timer: ResMut<JumpTimer>,
+    mut player_damaged_event_writer: EventWriter<PlayerDamaged>,
+    mut screen_shake_event_writer: EventWriter<ScreenShake>,
+    mut hit_stop_event_writer: EventWriter<HitStop>,
+    mut play_sfx_event_writer: EventWriter<PlaySfx>,
+    mut spawn_particles_event_writer: EventWriter<SpawnParticles>
+) {
+    let Ok((player, interpolated, mut jumps, mut dash)) = player_query.get_single_mut() else {
+        return;
+    };
+    // where they were before the move that ran them into it
+    let feet_before = interpolated.previous.y - HALF_PLAYER;
+    let coming_down = interpolated.current.y <= interpolated.previous.y;
+
+    let mut stomped = false;
+    let mut hurt = None;
+    for (entity, transform, enemy) in enemy_query.iter() {
+        if rapier_context.intersection_pair(player, entity) != Some(true) {
+            continue;
+        }
+
+        let top = transform.translation.y + enemy.half_size.y;
+        if coming_down && feet_before >= top - STOMP_TOLERANCE {
+            stomped = true;
+            commands.entity(entity).despawn_recursive();
+            spawn_particles_event_writer.send(SpawnParticles {
+                effect: "landing_puff",
+                position: Vec2::new(transform.translation.x, top),
+                source: None,
+            });
+        } else {
+            hurt = Some(PlayerDamaged {
+                damage: enemy.damage,
+                source: transform.translation.truncate(),
+            });
+        }
+    }
+
+    // landing on one while brushing another still counts as a stomp
+    if stomped {
+        bounce_player(&mut jumps, &mut dash, &mut jump_timer);
+        screen_shake_event_writer.send(ScreenShake { trauma: STOMP_TRAUMA });
+        hit_stop_event_writer.send(HitStop { ticks: STOMP_HIT_STOP_TICKS });
+        play_sfx_event_writer.send(PlaySfx(SfxId::Stomp));
+    } else if let Some(hurt) = hurt {
+        player_damaged_event_writer.send(hurt);
+    }
+}
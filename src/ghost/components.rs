@@ -0,0 +1,10 @@
+use bevy::prelude::*;
+
+// -- COMPONENTS --
+
+// see-through copy of the player running the level's best recorded run
+#[derive(Component)]
+pub struct GhostPlayer {
+    // index into the run's positions for the next tick
+    pub frame: usize,
+}
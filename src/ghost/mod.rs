@@ -0,0 +1,32 @@
+// import bevy crates
+use bevy::prelude::*;
+
+// import modules
+pub mod components;
+pub mod resources;
+pub mod systems;
+
+// use modules
+use resources::*;
+use systems::*;
+use crate::level::systems::spawn_current_level;
+use crate::{ player_movement, simulation_running, AppState };
+
+// the player's colour, see-through
+pub const GHOST_COLOR: Color = Color::rgba(0.0, 1.0, 0.0, 0.35);
+
+pub struct GhostPlugin;
+
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Ghosts>()
+            .init_resource::<GhostRecording>()
+            .add_systems(OnEnter(AppState::Game), spawn_ghost.after(spawn_current_level))
+            .add_systems(OnEnter(AppState::LevelResults), keep_best_ghost)
+            // both look at where the player is before this tick's move, same as replays
+            .add_systems(
+                FixedUpdate,
+                (record_ghost_frame, move_ghost).before(player_movement).run_if(simulation_running)
+            );
+    }
+}
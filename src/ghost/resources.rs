@@ -0,0 +1,29 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{ Deserialize, Serialize };
+
+// -- RESOURCES --
+
+// the best run on each level in the active save slot, keyed by level id
+#[derive(Resource, Serialize, Deserialize, Default, Clone)]
+pub struct Ghosts {
+    pub levels: BTreeMap<String, GhostRun>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GhostRun {
+    // the level's geometry hash when this was recorded, see LevelData::geometry_hash
+    pub geometry_hash: u64,
+    // ticks a second it was recorded at
+    pub tick_rate: u32,
+    // where the player was on every tick of the run, rounded to whole pixels
+    // since the ghost doesn't need to be any more exact than that
+    pub positions: Vec<(i32, i32)>,
+}
+
+// the attempt being played right now, becomes the ghost if it's the fastest
+#[derive(Resource, Default)]
+pub struct GhostRecording {
+    pub positions: Vec<(i32, i32)>,
+}
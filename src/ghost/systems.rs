@@ -0,0 +1,141 @@
+use bevy::prelude::*;
+
+use crate::ghost::components::*;
+use crate::ghost::resources::*;
+use crate::ghost::GHOST_COLOR;
+use crate::interpolation::components::{ Interpolated, InterpolatedVisual };
+use crate::level::components::LevelEntity;
+use crate::level::resources::{ Campaign, CurrentLevel };
+use crate::save::resources::SaveRequested;
+use crate::settings::resources::Settings;
+use crate::{ Player, TickRate, HALF_PLAYER };
+
+// -- GHOST --
+
+pub fn spawn_ghost(
+    mut commands: Commands,
+    campaign: Res<Campaign>,
+    current_level: Res<CurrentLevel>,
+    settings: Res<Settings>,
+    tick_rate: Res<TickRate>,
+    mut ghosts: ResMut<Ghosts>,
+    mut ghost_recording: ResMut<GhostRecording>,
+    mut save_requested_event_writer: EventWriter<SaveRequested>
+) {
+    ghost_recording.positions.clear();
+
+    let id = &campaign.levels[current_level.index].id;
+    let Some(ghost_run) = ghosts.levels.get(id) else {
+        return;
+    };
+
+    // the level's been changed since the run was recorded, it'd go through walls now
+    if ghost_run.geometry_hash != current_level.geometry_hash {
+        info!("{} has changed since its ghost was recorded, clearing it", id);
+        ghosts.levels.remove(id);
+        save_requested_event_writer.send(SaveRequested {});
+        return;
+    }
+
+    // a run from another tick rate would play back too fast or too slow
+    if ghost_run.tick_rate != tick_rate.hz {
+        info!("{}'s ghost was recorded at a different tick rate, clearing it", id);
+        ghosts.levels.remove(id);
+        save_requested_event_writer.send(SaveRequested {});
+        return;
+    }
+
+    if !settings.show_ghost {
+        return;
+    }
+
+    let (x, y) = ghost_run.positions.first().copied().unwrap_or_default();
+    let position = Vec2::new(x as f32, y as f32);
+    commands
+        .spawn((
+            // just behind the player
+            SpatialBundle::from_transform(Transform::from_translation(position.extend(-0.1))),
+            Interpolated::new(position),
+            GhostPlayer { frame: 0 },
+            LevelEntity {},
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: GHOST_COLOR,
+                        custom_size: Some(Vec2::splat(HALF_PLAYER * 2.0)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                InterpolatedVisual {},
+            ));
+        });
+}
+
+pub fn record_ghost_frame(
+    player_query: Query<&Transform, With<Player>>,
+    mut ghost_recording: ResMut<GhostRecording>
+) {
+    if let Ok(transform) = player_query.get_single() {
+        ghost_recording.positions.push((
+            transform.translation.x.round() as i32,
+            transform.translation.y.round() as i32,
+        ));
+    }
+}
+
+pub fn move_ghost(
+    campaign: Res<Campaign>,
+    current_level: Res<CurrentLevel>,
+    ghosts: Res<Ghosts>,
+    mut ghost_query: Query<(&mut Transform, &mut Visibility, &mut GhostPlayer)>
+) {
+    let Some(ghost_run) = ghosts.levels.get(&campaign.levels[current_level.index].id) else {
+        return;
+    };
+
+    for (mut transform, mut visibility, mut ghost) in ghost_query.iter_mut() {
+        match ghost_run.positions.get(ghost.frame) {
+            Some((x, y)) => {
+                transform.translation.x = *x as f32;
+                transform.translation.y = *y as f32;
+                ghost.frame += 1;
+            }
+            // the ghost's already finished, get it out of the way
+            None => {
+                *visibility = Visibility::Hidden;
+            }
+        }
+    }
+}
+
+// the run that just finished replaces the ghost if it took fewer ticks
+pub fn keep_best_ghost(
+    campaign: Res<Campaign>,
+    current_level: Res<CurrentLevel>,
+    tick_rate: Res<TickRate>,
+    mut ghosts: ResMut<Ghosts>,
+    mut ghost_recording: ResMut<GhostRecording>,
+    mut save_requested_event_writer: EventWriter<SaveRequested>
+) {
+    let id = &campaign.levels[current_level.index].id;
+    let positions = std::mem::take(&mut ghost_recording.positions);
+    if positions.is_empty() {
+        return;
+    }
+
+    let is_best = ghosts.levels
+        .get(id)
+        .is_none_or(|ghost_run| positions.len() < ghost_run.positions.len());
+
+    if is_best {
+        ghosts.levels.insert(id.clone(), GhostRun {
+            geometry_hash: current_level.geometry_hash,
+            tick_rate: tick_rate.hz,
+            positions,
+        });
+        save_requested_event_writer.send(SaveRequested {});
+    }
+}
@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+
+// -- COMPONENTS --
+
+// anything that moves on ticks and gets drawn through an InterpolatedVisual child.
+// keeps the last two ticks' positions so frames in between can be blended
+#[derive(Component)]
+pub struct Interpolated {
+    pub previous: Vec2,
+    pub current: Vec2,
+}
+
+impl Interpolated {
+    pub fn new(position: Vec2) -> Interpolated {
+        Interpolated {
+            previous: position,
+            current: position,
+        }
+    }
+
+    // for teleports, so it doesn't get drawn sliding across the level
+    pub fn snap_to(&mut self, position: Vec2) {
+        self.previous = position;
+        self.current = position;
+    }
+
+    // fraction is how far through the next tick the frame is, 0.0 to 1.0
+    pub fn position(&self, fraction: f32) -> Vec2 {
+        self.previous.lerp(self.current, fraction)
+    }
+}
+
+// the drawn part of an Interpolated entity, offset each frame to where it should appear
+#[derive(Component)]
+pub struct InterpolatedVisual {}
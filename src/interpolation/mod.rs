@@ -0,0 +1,23 @@
+// import bevy crates
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+// import modules
+pub mod components;
+pub mod systems;
+
+// use modules
+use systems::*;
+
+pub struct InterpolationPlugin;
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        // positions are noted down once everything, physics included, has moved for the tick
+        app.add_systems(FixedPostUpdate, record_tick_positions)
+            .add_systems(
+                PostUpdate,
+                interpolate_visuals.before(TransformSystem::TransformPropagate)
+            );
+    }
+}
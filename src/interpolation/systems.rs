@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+
+use crate::interpolation::components::*;
+
+pub fn record_tick_positions(mut interpolated_query: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in interpolated_query.iter_mut() {
+        interpolated.previous = interpolated.current;
+        interpolated.current = transform.translation.truncate();
+    }
+}
+
+// only ever moves the visual, the simulation never sees these positions
+pub fn interpolate_visuals(
+    fixed_time: Res<Time<Fixed>>,
+    parent_query: Query<(&Transform, &Interpolated), Without<InterpolatedVisual>>,
+    mut visual_query: Query<(&Parent, &mut Transform), With<InterpolatedVisual>>
+) {
+    let fraction = fixed_time.overstep_fraction();
+
+    for (parent, mut transform) in visual_query.iter_mut() {
+        if let Ok((parent_transform, interpolated)) = parent_query.get(parent.get()) {
+            let offset = interpolated.position(fraction) - parent_transform.translation.truncate();
+            transform.translation.x = offset.x;
+            transform.translation.y = offset.y;
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+use crate::juice::{ LANDING_SQUASH, MAX_IMPACT_SPEED };
+
+// -- COMPONENTS --
+
+// springy squash and stretch on a drawn child, the collider on the parent never changes
+#[derive(Component, Default)]
+pub struct SquashStretch {
+    // how far it's stretched up, negative is squashed flat. width shrinks to match so it
+    // keeps the same area
+    pub stretch: f32,
+    pub velocity: f32,
+    // radians, tipped over into a dash
+    pub lean: f32,
+}
+
+impl SquashStretch {
+    // landing flattens it more the faster it hit, in pixels a second
+    pub fn land(&mut self, impact_speed: f32) {
+        self.velocity -= impact_speed.min(MAX_IMPACT_SPEED) * LANDING_SQUASH;
+    }
+}
@@ -0,0 +1,78 @@
+// import bevy crates
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+// import modules
+pub mod components;
+pub mod resources;
+pub mod systems;
+
+// use modules
+use resources::*;
+use systems::*;
+use crate::camera::systems::camera_follow;
+use crate::interpolation::systems::interpolate_visuals;
+use crate::{ AppState, SimulationState };
+
+// trauma lost per second, so a full 1.0 shakes for a second
+pub const TRAUMA_DECAY: f32 = 1.0;
+// how far the camera gets knocked at full trauma, in pixels and radians
+pub const MAX_SHAKE_OFFSET: f32 = 24.0;
+pub const MAX_SHAKE_ANGLE: f32 = 0.05;
+// how quickly the shake wobbles, higher is more jittery
+pub const SHAKE_FREQUENCY: f32 = 25.0;
+
+// how far the player has to fall before landing shakes the screen
+pub const HEAVY_LANDING_HEIGHT: f32 = 250.0;
+pub const DASH_TRAUMA: f32 = 0.15;
+pub const DEATH_TRAUMA: f32 = 0.5;
+pub const HURT_TRAUMA: f32 = 0.3;
+// the little freeze as a dash starts that makes it feel like it has some punch
+pub const DASH_HIT_STOP_TICKS: u32 = 3;
+pub const HURT_HIT_STOP_TICKS: u32 = 4;
+
+// the spring squash and stretch bounces back on, lower damping wobbles for longer
+pub const SQUASH_STIFFNESS: f32 = 300.0;
+pub const SQUASH_DAMPING: f32 = 14.0;
+// how far it stretches up while jumping
+pub const JUMP_STRETCH: f32 = 0.15;
+// how hard landing kicks the squash for each pixel a second the player came down at,
+// capped so a long fall doesn't flatten them completely
+pub const LANDING_SQUASH: f32 = 0.005;
+pub const MAX_IMPACT_SPEED: f32 = 1500.0;
+pub const DASH_LEAN: f32 = 0.2;
+
+pub struct JuicePlugin;
+
+impl Plugin for JuicePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Trauma>()
+            .init_resource::<HitStopTimer>()
+            .add_event::<ScreenShake>()
+            .add_event::<HitStop>()
+            // decided at the very start of each tick so the whole tick freezes or none of it
+            .add_systems(
+                FixedFirst,
+                tick_hit_stop.run_if(
+                    in_state(AppState::Game).and_then(in_state(SimulationState::Running))
+                )
+            )
+            // on top of wherever camera_follow put the camera this frame
+            .add_systems(
+                Update,
+                (add_trauma, apply_screen_shake.after(add_trauma))
+                    .after(camera_follow)
+                    .run_if(in_state(AppState::Game).and_then(in_state(SimulationState::Running)))
+            )
+            // on top of where interpolate_visuals put the visual this frame
+            .add_systems(
+                PostUpdate,
+                squash_and_stretch
+                    .after(interpolate_visuals)
+                    .before(TransformSystem::TransformPropagate)
+                    .run_if(in_state(AppState::Game).and_then(in_state(SimulationState::Running)))
+            )
+            .add_systems(OnEnter(SimulationState::Paused), unfreeze_while_paused)
+            .add_systems(OnExit(AppState::Game), clear_juice);
+    }
+}
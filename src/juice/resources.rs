@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+
+// -- RESOURCES --
+
+// how shaken up the camera is, 0.0 to 1.0. the shake goes with the square of this so
+// small hits barely move it and big ones really kick
+#[derive(Resource, Default)]
+pub struct Trauma {
+    pub amount: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct HitStopTimer {
+    pub ticks_left: u32,
+    // whether the tick that's running right now is frozen
+    pub frozen: bool,
+}
+
+// -- EVENTS --
+
+// any system can send this, trauma adds up and tops out at 1.0
+#[derive(Event)]
+pub struct ScreenShake {
+    pub trauma: f32,
+}
+
+// freezes the simulation for this many ticks, starting next tick. overlapping
+// hit-stops don't stack, the longer one wins
+#[derive(Event)]
+pub struct HitStop {
+    pub ticks: u32,
+}
@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::camera::components::CameraController;
+use crate::juice::components::*;
+use crate::juice::resources::*;
+use crate::juice::{
+    DASH_LEAN,
+    JUMP_STRETCH,
+    MAX_SHAKE_ANGLE,
+    MAX_SHAKE_OFFSET,
+    SHAKE_FREQUENCY,
+    SQUASH_DAMPING,
+    SQUASH_STIFFNESS,
+    TRAUMA_DECAY,
+};
+use crate::settings::resources::Settings;
+use crate::{ Dash, Jumps, Player, HALF_PLAYER };
+
+// -- SCREEN SHAKE --
+
+pub fn add_trauma(
+    time: Res<Time>,
+    mut screen_shake_event_reader: EventReader<ScreenShake>,
+    mut trauma: ResMut<Trauma>
+) {
+    let added: f32 = screen_shake_event_reader
+        .read()
+        .map(|event| event.trauma)
+        .sum();
+    trauma.amount = (trauma.amount - TRAUMA_DECAY * time.delta_seconds() + added).clamp(0.0, 1.0);
+}
+
+// knocks the camera away from where it's meant to be without changing where it's meant to
+// be, so the follow smoothing never sees the shake
+pub fn apply_screen_shake(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    trauma: Res<Trauma>,
+    mut camera_query: Query<(&mut Transform, &CameraController)>
+) {
+    let Ok((mut transform, controller)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    let shake = trauma.amount * trauma.amount * settings.screen_shake;
+    let t = time.elapsed_seconds() * SHAKE_FREQUENCY;
+
+    transform.translation.x = controller.position.x + MAX_SHAKE_OFFSET * shake * wobble(t, 0.0);
+    transform.translation.y = controller.position.y + MAX_SHAKE_OFFSET * shake * wobble(t, 10.0);
+    transform.rotation = Quat::from_rotation_z(MAX_SHAKE_ANGLE * shake * wobble(t, 20.0));
+}
+
+pub fn clear_juice(
+    mut trauma: ResMut<Trauma>,
+    mut hit_stop_timer: ResMut<HitStopTimer>,
+    mut camera_query: Query<&mut Transform, With<CameraController>>
+) {
+    trauma.amount = 0.0;
+    *hit_stop_timer = HitStopTimer::default();
+    if let Ok(mut transform) = camera_query.get_single_mut() {
+        transform.rotation = Quat::IDENTITY;
+    }
+}
+
+// smooth-ish wander between -1.0 and 1.0, offset picks a different path for each axis.
+// just sines so it doesn't pull numbers out of GameRng and throw off replays
+fn wobble(t: f32, offset: f32) -> f32 {
+    ((t + offset).sin() + (t * 2.3 + offset * 1.7).sin() * 0.5) / 1.5
+}
+
+// -- SQUASH AND STRETCH --
+
+// springs the visual towards whatever shape the player's movement calls for, landings
+// kick it from track_falls
+pub fn squash_and_stretch(
+    time: Res<Time>,
+    hit_stop_timer: Res<HitStopTimer>,
+    player_query: Query<(&Jumps, &Dash), With<Player>>,
+    mut visual_query: Query<(&Parent, &mut SquashStretch, &mut Transform)>
+) {
+    // a long frame would throw the spring way past where it's going
+    let dt = time.delta_seconds().min(1.0 / 30.0);
+
+    for (parent, mut squash, mut transform) in visual_query.iter_mut() {
+        let Ok((jumps, dash)) = player_query.get(parent.get()) else {
+            continue;
+        };
+
+        // holds its shape through a hit-stop like everything else
+        if !hit_stop_timer.frozen {
+            let target = if jumps.is_jumping { JUMP_STRETCH } else { 0.0 };
+            let force = (target - squash.stretch) * SQUASH_STIFFNESS -
+                squash.velocity * SQUASH_DAMPING;
+            squash.velocity += force * dt;
+            squash.stretch += squash.velocity * dt;
+
+            let lean = if dash.is_dashing { -dash.direction.x * DASH_LEAN } else { 0.0 };
+            squash.lean += (lean - squash.lean) * (1.0 - (-SQUASH_DAMPING * dt).exp());
+        }
+
+        let height = (1.0 + squash.stretch).max(0.2);
+        transform.scale = Vec3::new(1.0 / height, height, 1.0);
+        transform.rotation = Quat::from_rotation_z(squash.lean);
+        // squashes down onto its feet rather than in towards its middle
+        transform.translation.y += (height - 1.0) * HALF_PLAYER;
+    }
+}
+
+// -- HIT-STOP --
+
+pub fn tick_hit_stop(
+    mut hit_stop_event_reader: EventReader<HitStop>,
+    mut hit_stop_timer: ResMut<HitStopTimer>,
+    mut rapier_config: ResMut<RapierConfiguration>
+) {
+    for event in hit_stop_event_reader.read() {
+        hit_stop_timer.ticks_left = hit_stop_timer.ticks_left.max(event.ticks);
+    }
+
+    hit_stop_timer.frozen = hit_stop_timer.ticks_left > 0;
+    if hit_stop_timer.frozen {
+        hit_stop_timer.ticks_left -= 1;
+    }
+    rapier_config.physics_pipeline_active = !hit_stop_timer.frozen;
+}
+
+// pausing already stops everything, and input should keep getting cleared out each tick
+// so presses in the pause menu don't turn up in the game afterwards. whatever's left of
+// the hit-stop picks back up on the first tick after unpausing
+pub fn unfreeze_while_paused(mut hit_stop_timer: ResMut<HitStopTimer>) {
+    hit_stop_timer.frozen = false;
+}
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+// -- COMPONENTS --
+
+// everything spawned from a level file gets this so the whole level
+// can be despawned at once when moving on to the next one
+#[derive(Component)]
+pub struct LevelEntity {}
+
+// sensor that finishes the level when the player touches it
+#[derive(Component)]
+pub struct LevelExit {}
+
+// optional pickup, counted towards the level's collectible total
+#[derive(Component)]
+pub struct Collectible {}
+
+// invisible sensor that plays the checkpoint stinger, gone once it's been touched
+#[derive(Component)]
+pub struct Checkpoint {}
+
+#[derive(Component)]
+pub struct ResultsScreen {}
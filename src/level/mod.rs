@@ -0,0 +1,62 @@
+// import bevy crates
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::PhysicsSet;
+
+// import modules
+pub mod components;
+pub mod resources;
+pub mod systems;
+
+// use modules
+use resources::*;
+use systems::*;
+use crate::data::load_ron;
+use crate::{ simulation_running, AppState };
+
+pub const CAMPAIGN_FILE: &str = "levels/campaign.ron";
+pub const EXIT_COLOR: Color = Color::GOLD;
+pub const COLLECTIBLE_COLOR: Color = Color::CYAN;
+pub const COLLECTIBLE_RADIUS: f32 = 10.0;
+pub const CHECKPOINT_SIZE: (f32, f32) = (20.0, 80.0);
+pub const RESULTS_BACKGROUND_COLOR: Color = Color::rgb(0.1, 0.1, 0.12);
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        let mut campaign: Campaign = load_ron(CAMPAIGN_FILE).unwrap();
+        // the manifest doesn't list collectibles so count them from each level file
+        for entry in campaign.levels.iter_mut() {
+            let level: LevelData = load_ron(&entry.file).unwrap();
+            entry.total_collectibles = level.collectibles.len() as u32;
+        }
+
+        app.insert_resource(campaign)
+            .init_resource::<CampaignProgress>()
+            .init_resource::<CurrentLevel>()
+            .init_resource::<LevelStats>()
+            .init_resource::<LevelResults>()
+            .add_event::<CheckpointReached>()
+            .add_systems(OnEnter(AppState::Game), spawn_current_level)
+            .add_systems(OnExit(AppState::Game), despawn_level)
+            .add_systems(OnEnter(AppState::LevelResults), spawn_results_screen)
+            .add_systems(OnExit(AppState::LevelResults), despawn_results_screen)
+            .add_systems(
+                FixedUpdate,
+                (
+                    tick_level_stats,
+                    count_deaths,
+                    player_collects,
+                    player_reaches_checkpoint,
+                    player_reaches_exit,
+                    boss_unlocks_next_level,
+                )
+                    .before(PhysicsSet::SyncBackend)
+                    .run_if(simulation_running)
+            )
+            .add_systems(
+                Update,
+                continue_from_results.run_if(in_state(AppState::LevelResults))
+            );
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use bevy::time::*;
+use serde::{ Deserialize, Serialize };
+
+use crate::boss::resources::BossData;
+use crate::camera::resources::{ CameraParams, CameraZoneData };
+use crate::data::hash_floats;
+use crate::enemy::resources::EnemyData;
+use crate::parallax::resources::ParallaxLayerData;
+use crate::sound::resources::{ MusicStemData, MusicTriggerData };
+use crate::turret::resources::TurretData;
+use crate::default_damage;
+
+// -- RESOURCES --
+
+// the campaign manifest, every level in the order they're played
+#[derive(Resource, Deserialize)]
+pub struct Campaign {
+    pub levels: Vec<CampaignEntry>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct CampaignEntry {
+    pub id: String,
+    pub name: String,
+    pub file: String,
+    // filled in from the level file when the campaign is loaded
+    #[serde(skip)]
+    pub total_collectibles: u32,
+}
+
+// index into Campaign.levels of the level being played
+#[derive(Resource, Default)]
+pub struct CurrentLevel {
+    pub index: usize,
+    // LevelData::geometry_hash of the level as it was loaded
+    pub geometry_hash: u64,
+}
+
+// time, deaths and pickups for the current attempt at a level
+#[derive(Resource, Default)]
+pub struct LevelStats {
+    pub time: Stopwatch,
+    pub deaths: u32,
+    pub collectibles: u32,
+    pub total_collectibles: u32,
+}
+
+// what gets shown on the results screen after finishing a level
+#[derive(Resource, Default)]
+pub struct LevelResults {
+    pub name: String,
+    pub time: f32,
+    pub deaths: u32,
+    pub collectibles: u32,
+    pub total_collectibles: u32,
+    pub is_last_level: bool,
+}
+
+// everything the player has done in the campaign, keyed by level id
+#[derive(Resource, Serialize, Deserialize, Default, Clone)]
+pub struct CampaignProgress {
+    pub levels: BTreeMap<String, LevelProgress>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct LevelProgress {
+    pub unlocked: bool,
+    pub best_time: Option<f32>,
+    // deaths across every attempt, not just the best one
+    pub deaths: u32,
+    // most collectibles grabbed in a single run
+    pub collectibles: u32,
+}
+
+impl CampaignProgress {
+    pub fn level(&self, id: &str) -> LevelProgress {
+        self.levels.get(id).cloned().unwrap_or_default()
+    }
+
+    // the first level is always open
+    pub fn is_unlocked(&self, campaign: &Campaign, index: usize) -> bool {
+        index == 0 || self.level(&campaign.levels[index].id).unlocked
+    }
+
+    // percentage of levels finished and collectibles found
+    pub fn completion(&self, campaign: &Campaign) -> f32 {
+        let mut total = 0;
+        let mut done = 0;
+
+        for entry in campaign.levels.iter() {
+            let level_progress = self.level(&entry.id);
+            total += 1 + entry.total_collectibles;
+            if level_progress.best_time.is_some() {
+                done += 1;
+            }
+            done += level_progress.collectibles.min(entry.total_collectibles);
+        }
+
+        if total == 0 {
+            0.0
+        } else {
+            ((done as f32) / (total as f32)) * 100.0
+        }
+    }
+}
+
+// -- EVENTS --
+
+// the player touched a checkpoint for the first time this attempt
+#[derive(Event)]
+pub struct CheckpointReached {}
+
+// -- LEVEL DATA --
+
+// one level file, sizes are full width/height and positions are centers
+#[derive(Deserialize)]
+pub struct LevelData {
+    pub spawn: (f32, f32),
+    pub platforms: Vec<PlatformData>,
+    #[serde(default)]
+    pub spikes: Vec<SpikeData>,
+    #[serde(default)]
+    pub collectibles: Vec<CollectibleData>,
+    // only play a stinger the first time they're touched each attempt
+    #[serde(default)]
+    pub checkpoints: Vec<CheckpointData>,
+    #[serde(default)]
+    pub enemies: Vec<EnemyData>,
+    #[serde(default)]
+    pub turrets: Vec<TurretData>,
+    #[serde(default)]
+    pub boss: Option<BossData>,
+    pub exit: ExitData,
+    #[serde(default)]
+    pub camera: CameraParams,
+    #[serde(default)]
+    pub camera_zones: Vec<CameraZoneData>,
+    // background images, furthest away first
+    #[serde(default)]
+    pub backgrounds: Vec<ParallaxLayerData>,
+    // looping track under assets/, none plays nothing
+    #[serde(default)]
+    pub music: Option<String>,
+    // layers over the music that fade in and out with the named parameters
+    #[serde(default)]
+    pub music_stems: Vec<MusicStemData>,
+    #[serde(default)]
+    pub music_triggers: Vec<MusicTriggerData>,
+}
+
+impl LevelData {
+    // changes whenever anything the player can run into moves, so runs recorded
+    // on an older layout of the level can be told apart
+    pub fn geometry_hash(&self) -> u64 {
+        let mut values = vec![self.spawn.0, self.spawn.1];
+        for platform in self.platforms.iter() {
+            values.extend([
+                platform.size.0,
+                platform.size.1,
+                platform.position.0,
+                platform.position.1,
+            ]);
+        }
+        for spike in self.spikes.iter() {
+            values.extend([spike.position.0, spike.position.1]);
+        }
+        for enemy in self.enemies.iter() {
+            values.extend([enemy.position.0, enemy.position.1, enemy.size.0, enemy.size.1]);
+        }
+        for turret in self.turrets.iter() {
+            values.extend([turret.position.0, turret.position.1]);
+        }
+        if let Some(boss) = self.boss.as_ref() {
+            values.extend([boss.position.0, boss.position.1, boss.size.0, boss.size.1]);
+            for door in boss.doors.iter().chain(boss.platforms.iter()) {
+                values.extend([door.size.0, door.size.1, door.position.0, door.position.1]);
+            }
+        }
+        values.extend([
+            self.exit.size.0,
+            self.exit.size.1,
+            self.exit.position.0,
+            self.exit.position.1,
+        ]);
+
+        hash_floats(&values)
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct PlatformData {
+    pub size: (f32, f32),
+    pub position: (f32, f32),
+}
+
+// spikes are positioned by their bottom left corner
+#[derive(Deserialize)]
+pub struct SpikeData {
+    pub position: (f32, f32),
+    #[serde(default = "default_damage")]
+    pub damage: u32,
+}
+
+#[derive(Deserialize)]
+pub struct CollectibleData {
+    pub position: (f32, f32),
+}
+
+// position is the center, it's CHECKPOINT_SIZE
+#[derive(Deserialize)]
+pub struct CheckpointData {
+    pub position: (f32, f32),
+}
+
+#[derive(Deserialize)]
+pub struct ExitData {
+    pub size: (f32, f32),
+    pub position: (f32, f32),
+}
@@ -0,0 +1,440 @@
+use bevy::prelude::*;
+use bevy::sprite::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::boss::resources::{ BossDefeated, LevelBoss };
+use crate::camera::resources::CameraZones;
+use crate::controls::resources::ActionState;
+use crate::data::load_ron;
+use crate::enemy::resources::LevelEnemies;
+use crate::interpolation::components::Interpolated;
+use crate::level::components::*;
+use crate::level::resources::*;
+use crate::level::{
+    CHECKPOINT_SIZE,
+    COLLECTIBLE_COLOR,
+    COLLECTIBLE_RADIUS,
+    EXIT_COLOR,
+    RESULTS_BACKGROUND_COLOR,
+};
+use crate::menu::systems::MenuInput;
+use crate::parallax::resources::ParallaxLayers;
+use crate::save::resources::SaveRequested;
+use crate::sound::components::MusicTrigger;
+use crate::sound::resources::{ LevelMusic, PlaySfx, SfxId };
+use crate::turret::resources::LevelTurrets;
+use crate::{
+    AppState,
+    Dash,
+    DashTimer,
+    FallTracker,
+    Hazard,
+    JumpTimer,
+    Jumps,
+    PlatformBundle,
+    Player,
+    PlayerDied,
+    PlayerSpawn,
+    SPIKE_COLOR,
+};
+
+// -- LEVEL LOADING --
+
+pub fn spawn_current_level(
+    mut commands: Commands,
+    campaign: Res<Campaign>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut player_spawn: ResMut<PlayerSpawn>,
+    mut level_stats: ResMut<LevelStats>,
+    mut jump_timer: ResMut<JumpTimer>,
+    mut dash_timer: ResMut<DashTimer>,
+    mut player_query: Query<
+        (Entity, &mut Transform, &mut Velocity, &mut Jumps, &mut Dash),
+        With<Player>
+    >,
+    mut interpolated_query: Query<(&mut Interpolated, &mut FallTracker), With<Player>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>
+) {
+    let entry = &campaign.levels[current_level.index];
+    let level: LevelData = load_ron(&entry.file).unwrap();
+    info!("loading level {}", entry.id);
+    current_level.geometry_hash = level.geometry_hash();
+
+    for platform in level.platforms.iter() {
+        commands.spawn((
+            PlatformBundle::new(
+                platform.size.0,
+                platform.size.1,
+                platform.position.0,
+                platform.position.1
+            ),
+            LevelEntity {},
+        ));
+    }
+
+    for spike in level.spikes.iter() {
+        commands
+            .spawn((
+                MaterialMesh2dBundle {
+                    mesh: Mesh2dHandle(
+                        meshes.add(
+                            Triangle2d::new(
+                                Vec2::new(0.0, 0.0),
+                                Vec2::new(50.0, 0.0),
+                                Vec2::new(25.0, 75.0)
+                            )
+                        )
+                    ),
+                    material: materials.add(SPIKE_COLOR),
+                    ..default()
+                },
+                RigidBody::Fixed,
+                Hazard {
+                    damage: spike.damage,
+                    center: Vec2::new(spike.position.0 + 25.0, spike.position.1 + 25.0),
+                },
+                LevelEntity {},
+            ))
+            .insert((
+                TransformBundle::from(
+                    Transform::from_xyz(spike.position.0, spike.position.1, 0.0)
+                ),
+                Collider::triangle(
+                    Vec2::new(0.0, 0.0),
+                    Vec2::new(50.0, 0.0),
+                    Vec2::new(25.0, 75.0)
+                ),
+                Sensor,
+            ));
+    }
+
+    for collectible in level.collectibles.iter() {
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(meshes.add(Circle::new(COLLECTIBLE_RADIUS))),
+                material: materials.add(COLLECTIBLE_COLOR),
+                transform: Transform::from_xyz(
+                    collectible.position.0,
+                    collectible.position.1,
+                    0.0
+                ),
+                ..default()
+            },
+            Collider::ball(COLLECTIBLE_RADIUS),
+            Sensor,
+            Collectible {},
+            LevelEntity {},
+        ));
+    }
+
+    // invisible, they only play the stinger
+    for checkpoint in level.checkpoints.iter() {
+        commands.spawn((
+            TransformBundle::from(
+                Transform::from_xyz(checkpoint.position.0, checkpoint.position.1, 0.0)
+            ),
+            Collider::cuboid(CHECKPOINT_SIZE.0 / 2.0, CHECKPOINT_SIZE.1 / 2.0),
+            Sensor,
+            Checkpoint {},
+            LevelEntity {},
+        ));
+    }
+
+    // invisible, they only change the music
+    for trigger in level.music_triggers.iter() {
+        commands.spawn((
+            TransformBundle::from(
+                Transform::from_xyz(trigger.position.0, trigger.position.1, 0.0)
+            ),
+            Collider::cuboid(trigger.size.0 / 2.0, trigger.size.1 / 2.0),
+            Sensor,
+            MusicTrigger {
+                parameter: trigger.parameter.clone(),
+                value: trigger.value,
+            },
+            LevelEntity {},
+        ));
+    }
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: EXIT_COLOR,
+                custom_size: Some(Vec2::new(level.exit.size.0, level.exit.size.1)),
+                ..default()
+            },
+            transform: Transform::from_xyz(level.exit.position.0, level.exit.position.1, 0.0),
+            ..default()
+        },
+        Collider::cuboid(level.exit.size.0 / 2.0, level.exit.size.1 / 2.0),
+        Sensor,
+        LevelExit {},
+        LevelEntity {},
+    ));
+
+    // move the player over to the new level's spawn, starting from scratch so
+    // nothing from the last level carries over and replays line up
+    player_spawn.position = Vec2::new(level.spawn.0, level.spawn.1);
+    if let Ok((player, mut transform, mut velocity, mut jumps, mut dash)) = player_query
+        .get_single_mut()
+    {
+        transform.translation = player_spawn.position.extend(0.0);
+        *velocity = Velocity::zero();
+        jumps.has_grounded_jump = false;
+        jumps.is_jumping = false;
+        jumps.is_bouncing = false;
+        dash.has_dash = false;
+        dash.is_dashing = false;
+        // rapier adds this back after the first move, until then the player isn't grounded
+        commands.entity(player).remove::<KinematicCharacterControllerOutput>();
+    }
+    // so it doesn't get drawn sliding over from wherever the last level ended, or
+    // land like it fell from there
+    if let Ok((mut interpolated, mut fall_tracker)) = interpolated_query.get_single_mut() {
+        interpolated.snap_to(player_spawn.position);
+        fall_tracker.highest = player_spawn.position.y;
+    }
+    *jump_timer = JumpTimer::default();
+    *dash_timer = DashTimer::default();
+
+    commands.insert_resource(level.camera);
+    commands.insert_resource(CameraZones { zones: level.camera_zones.clone() });
+    commands.insert_resource(ParallaxLayers { layers: level.backgrounds.clone() });
+    commands.insert_resource(LevelEnemies { enemies: level.enemies.clone() });
+    commands.insert_resource(LevelTurrets { turrets: level.turrets.clone() });
+    commands.insert_resource(LevelBoss { boss: level.boss.clone() });
+    commands.insert_resource(LevelMusic {
+        file: level.music.clone(),
+        stems: level.music_stems.clone(),
+    });
+
+    level_stats.time.reset();
+    level_stats.deaths = 0;
+    level_stats.collectibles = 0;
+    level_stats.total_collectibles = level.collectibles.len() as u32;
+}
+
+pub fn despawn_level(mut commands: Commands, level_query: Query<Entity, With<LevelEntity>>) {
+    for entity in level_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// -- LEVEL PROGRESS --
+
+// runs on ticks, so time is the fixed clock and this goes up by exactly one tick
+pub fn tick_level_stats(time: Res<Time>, mut level_stats: ResMut<LevelStats>) {
+    level_stats.time.tick(time.delta());
+}
+
+pub fn count_deaths(
+    mut player_died_event_reader: EventReader<PlayerDied>,
+    mut level_stats: ResMut<LevelStats>
+) {
+    for _ in player_died_event_reader.read() {
+        level_stats.deaths += 1;
+    }
+}
+
+pub fn player_collects(
+    mut commands: Commands,
+    rapier_context: Res<RapierContext>,
+    player_query: Query<Entity, With<Player>>,
+    collectible_query: Query<Entity, With<Collectible>>,
+    mut level_stats: ResMut<LevelStats>,
+    mut play_sfx_event_writer: EventWriter<PlaySfx>
+) {
+    if let Ok(player) = player_query.get_single() {
+        for collectible in collectible_query.iter() {
+            if rapier_context.intersection_pair(player, collectible) == Some(true) {
+                level_stats.collectibles += 1;
+                play_sfx_event_writer.send(PlaySfx(SfxId::Pickup));
+                commands.entity(collectible).despawn_recursive();
+            }
+        }
+    }
+}
+
+pub fn player_reaches_checkpoint(
+    mut commands: Commands,
+    rapier_context: Res<RapierContext>,
+    player_query: Query<Entity, With<Player>>,
+    checkpoint_query: Query<Entity, With<Checkpoint>>,
+    mut checkpoint_reached_event_writer: EventWriter<CheckpointReached>
+) {
+    if let Ok(player) = player_query.get_single() {
+        for checkpoint in checkpoint_query.iter() {
+            if rapier_context.intersection_pair(player, checkpoint) == Some(true) {
+                checkpoint_reached_event_writer.send(CheckpointReached {});
+                commands.entity(checkpoint).despawn_recursive();
+            }
+        }
+    }
+}
+
+pub fn player_reaches_exit(
+    rapier_context: Res<RapierContext>,
+    player_query: Query<Entity, With<Player>>,
+    exit_query: Query<Entity, With<LevelExit>>,
+    campaign: Res<Campaign>,
+    current_level: Res<CurrentLevel>,
+    level_stats: Res<LevelStats>,
+    mut progress: ResMut<CampaignProgress>,
+    mut save_requested_event_writer: EventWriter<SaveRequested>,
+    mut level_results: ResMut<LevelResults>,
+    mut next_app_state: ResMut<NextState<AppState>>
+) {
+    if let Ok(player) = player_query.get_single() {
+        for exit in exit_query.iter() {
+            if rapier_context.intersection_pair(player, exit) == Some(true) {
+                let time = level_stats.time.elapsed_secs();
+
+                *level_results = LevelResults {
+                    name: campaign.levels[current_level.index].name.clone(),
+                    time,
+                    deaths: level_stats.deaths,
+                    collectibles: level_stats.collectibles,
+                    total_collectibles: level_stats.total_collectibles,
+                    is_last_level: current_level.index + 1 >= campaign.levels.len(),
+                };
+
+                // record the run and open up the next level
+                let id = &campaign.levels[current_level.index].id;
+                let level_progress = progress.levels.entry(id.clone()).or_default();
+                level_progress.unlocked = true;
+                level_progress.deaths += level_stats.deaths;
+                level_progress.collectibles = level_progress.collectibles.max(
+                    level_stats.collectibles
+                );
+                if level_progress.best_time.is_none_or(|best| time < best) {
+                    level_progress.best_time = Some(time);
+                }
+
+                if let Some(next_level) = campaign.levels.get(current_level.index + 1) {
+                    progress.levels.entry(next_level.id.clone()).or_default().unlocked = true;
+                }
+
+                save_requested_event_writer.send(SaveRequested {});
+
+                next_app_state.set(AppState::LevelResults);
+            }
+        }
+    }
+}
+
+// beating a level's boss opens up the next one straight away, before the exit's reached
+pub fn boss_unlocks_next_level(
+    mut boss_defeated_event_reader: EventReader<BossDefeated>,
+    campaign: Res<Campaign>,
+    current_level: Res<CurrentLevel>,
+    mut progress: ResMut<CampaignProgress>,
+    mut save_requested_event_writer: EventWriter<SaveRequested>
+) {
+    if boss_defeated_event_reader.read().count() == 0 {
+        return;
+    }
+
+    if let Some(next_level) = campaign.levels.get(current_level.index + 1) {
+        progress.levels.entry(next_level.id.clone()).or_default().unlocked = true;
+        save_requested_event_writer.send(SaveRequested {});
+    }
+}
+
+// -- RESULTS SCREEN --
+
+pub fn spawn_results_screen(mut commands: Commands, level_results: Res<LevelResults>) {
+    let next_text = if level_results.is_last_level {
+        "Campaign complete! Press Jump to return to the menu"
+    } else {
+        "Press Jump to continue"
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: RESULTS_BACKGROUND_COLOR.into(),
+                ..default()
+            },
+            ResultsScreen {},
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    format!("{} complete", level_results.name),
+                    TextStyle { font_size: 48.0, ..default() }
+                )
+            );
+            parent.spawn(
+                TextBundle::from_section(
+                    format!("Time: {}", format_time(level_results.time)),
+                    TextStyle { font_size: 32.0, ..default() }
+                )
+            );
+            parent.spawn(
+                TextBundle::from_section(
+                    format!("Deaths: {}", level_results.deaths),
+                    TextStyle { font_size: 32.0, ..default() }
+                )
+            );
+            if level_results.total_collectibles > 0 {
+                parent.spawn(
+                    TextBundle::from_section(
+                        format!(
+                            "Collectibles: {}/{}",
+                            level_results.collectibles,
+                            level_results.total_collectibles
+                        ),
+                        TextStyle { font_size: 32.0, ..default() }
+                    )
+                );
+            }
+            parent.spawn(
+                TextBundle::from_section(next_text, TextStyle { font_size: 24.0, ..default() })
+            );
+        });
+}
+
+pub fn despawn_results_screen(
+    mut commands: Commands,
+    results_query: Query<Entity, With<ResultsScreen>>
+) {
+    for entity in results_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// same confirm as the menus, so rebinds and the gamepad work here too
+pub fn continue_from_results(
+    action_state: Res<ActionState>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    level_results: Res<LevelResults>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut next_app_state: ResMut<NextState<AppState>>
+) {
+    if MenuInput::read(&action_state, &keyboard_input, &gamepad_buttons).confirm {
+        if level_results.is_last_level {
+            // campaign's finished, back to level select
+            next_app_state.set(AppState::MainMenu);
+        } else {
+            current_level.index += 1;
+            next_app_state.set(AppState::Game);
+        }
+    }
+}
+
+// formats seconds as m:ss.mmm
+pub fn format_time(seconds: f32) -> String {
+    let minutes = (seconds / 60.0).floor() as u32;
+    format!("{}:{:06.3}", minutes, seconds - (minutes as f32) * 60.0)
+}
@@ -1,68 +1,190 @@
+// bevy systems tend to need a lot of parameters
+#![allow(clippy::too_many_arguments)]
+
 // -- EXTERNAL IMPORTS --
-use bevy::app::AppExit;
 use bevy::math::*;
 use bevy::prelude::*;
-use bevy::sprite::*;
 use bevy::time::*;
-use bevy::window::{ PrimaryWindow, WindowResolution };
+use bevy::window::PrimaryWindow;
 use bevy_rapier2d::prelude::*;
 
+// -- MODULES --
+mod animation;
+mod boss;
+mod camera;
+mod controls;
+mod data;
+mod enemy;
+mod ghost;
+mod interpolation;
+mod juice;
+mod level;
+mod menu;
+mod parallax;
+mod particles;
+mod replay;
+mod rng;
+mod save;
+mod settings;
+mod sound;
+mod speedrun;
+mod turret;
+
+use animation::components::Animator;
+use animation::resources::PlayerAnimations;
+use animation::AnimationPlugin;
+use boss::BossPlugin;
+use camera::CameraPlugin;
+use controls::resources::{ Action, ActionState, TickActions };
+use controls::ControlsPlugin;
+use enemy::EnemyPlugin;
+use ghost::GhostPlugin;
+use interpolation::components::{ Interpolated, InterpolatedVisual };
+use interpolation::InterpolationPlugin;
+use juice::components::SquashStretch;
+use juice::resources::{ HitStop, HitStopTimer, ScreenShake };
+use juice::{
+    JuicePlugin,
+    DASH_HIT_STOP_TICKS,
+    DASH_TRAUMA,
+    DEATH_TRAUMA,
+    HEAVY_LANDING_HEIGHT,
+    HURT_HIT_STOP_TICKS,
+    HURT_TRAUMA,
+};
+use level::LevelPlugin;
+use menu::MenuPlugin;
+use parallax::ParallaxPlugin;
+use particles::components::ParticleEmitter;
+use particles::resources::SpawnParticles;
+use particles::{ ParticlesPlugin, LANDING_PUFF_SPEED };
+use replay::resources::ReplayPlayback;
+use replay::ReplayPlugin;
+use save::file::load_settings;
+use save::SavePlugin;
+use settings::resources::Settings;
+use settings::SettingsPlugin;
+use sound::resources::{ PlaySfx, SfxId };
+use sound::SoundPlugin;
+use speedrun::SpeedrunPlugin;
+use turret::TurretPlugin;
+
 const WINDOW_WIDTH: f32 = 1024.0;
 const WINDOW_HEIGHT: f32 = 720.0;
 const HALF_PLAYER: f32 = 25.0;
 const TIME_TO_JUMP_EXPIRE: f32 = 0.4;
 const TIME_TO_DASH_EXPIRE: f32 = 0.3;
-const PLAYER_COLOR: Color = Color::GREEN;
+const TIME_TO_BOUNCE_EXPIRE: f32 = 0.25;
 const PLATFORM_COLOR: Color = Color::GRAY;
 const SPIKE_COLOR: Color = Color::WHITE;
 
-const PLAYER_GRAVITY: f32 = 15.0;
-const PLAYER_SPEED: f32 = 8.0;
-const JUMP_SPEED: f32 = 15.0;
-const DASH_SPEED: f32 = 20.0;
+// all in units a second, scaled by the tick length so the tick rate doesn't change how
+// the game plays
+const PLAYER_GRAVITY: f32 = 900.0;
+const PLAYER_SPEED: f32 = 480.0;
+const JUMP_SPEED: f32 = 900.0;
+const DASH_SPEED: f32 = 1200.0;
+const BOUNCE_SPEED: f32 = 900.0;
+const KNOCKBACK_SPEED: f32 = 500.0;
+const KNOCKBACK_LIFT: f32 = 450.0;
+// seconds a hit knocks the player back for, they've no control till it's over
+const KNOCKBACK_TIME: f32 = 0.2;
+// seconds after a hit before the next one counts
+const INVINCIBLE_TIME: f32 = 1.2;
+// seconds each blink lasts while invincible
+const INVINCIBLE_FLASH_INTERVAL: f32 = 0.08;
+// simulation ticks a second unless settings.ron says otherwise. gameplay and physics
+// only ever step by exactly one tick so the same inputs always play out the same way
+const DEFAULT_TICK_RATE: u32 = 60;
 
 fn main() {
+    // settings have to be read before the window opens
+    let settings = load_settings();
+    let mut window = Window {
+        title: "Rust/Bevy Capstone Platformer".to_string(),
+        ..default()
+    };
+    settings.apply_to_window(&mut window);
+
     App::new()
         // window plugin settings
         .add_plugins(
             DefaultPlugins.set(WindowPlugin {
-                primary_window: Some(Window {
-                    title: "Rust/Bevy Capstone Platformer".to_string(),
-                    resolution: WindowResolution::new(WINDOW_WIDTH, WINDOW_HEIGHT),
-                    resizable: false,
-                    ..default()
-                }),
+                primary_window: Some(window),
                 ..default()
             })
         )
-        // add rapier physics plugin
-        .add_plugins(RapierPhysicsPlugin::<()>::default())
+        // add rapier physics plugin, stepped once per tick alongside the gameplay systems
+        .add_plugins(RapierPhysicsPlugin::<()>::default().in_fixed_schedule())
         // add rendering to everything, showing 'hitboxes'
         // *** NOTE THIS PLUGIN WILL (SOMETIMES?) DISTORT COLORS OF EVERYTHING THAT USES RAPIER ***
         .add_plugins(RapierDebugRenderPlugin {
             mode: DebugRenderMode::all(),
             ..default()
         })
+        // a replay given on the command line swaps this for the rate it was recorded at
+        .insert_resource(TickRate { hz: settings.tick_rate.max(1) })
+        .insert_resource(settings)
+        // split in two, bevy only takes so many plugins in one tuple
+        .add_plugins((
+            AnimationPlugin,
+            CameraPlugin,
+            ControlsPlugin,
+            EnemyPlugin,
+            GhostPlugin,
+            InterpolationPlugin,
+            JuicePlugin,
+            LevelPlugin,
+        ))
+        .add_plugins((
+            MenuPlugin,
+            ParallaxPlugin,
+            ParticlesPlugin,
+            ReplayPlugin,
+            SettingsPlugin,
+            SoundPlugin,
+            SavePlugin,
+            SpeedrunPlugin,
+            TurretPlugin,
+            BossPlugin,
+        ))
         .init_resource::<JumpTimer>()
         .init_resource::<DashTimer>()
-        .init_state::<Direction>()
-        .init_state::<GravitySwitch>()
+        .init_resource::<PlayerSpawn>()
+        .add_event::<PlayerDied>()
+        .add_event::<PlayerDamaged>()
+        .init_resource::<Direction>()
+        .init_resource::<GravitySwitch>()
         .init_state::<SimulationState>()
         .init_state::<AppState>()
-        .add_systems(Startup, (
-            spawn_camera,
-            spawn_platforms.before(spawn_player),
-            spawn_spikes.after(spawn_platforms),
-            spawn_player.after(spawn_camera),
-        ))
-        .add_systems(Update, (
-            player_movement,
-            camera_follow.after(player_gravity),
-            player_gravity.after(player_movement),
-            check_grounded.after(player_gravity),
-            reset_player_to_spawn.after(check_grounded),
-            exit_game,
-        ))
+        .init_state::<MenuScreen>()
+        .add_systems(Startup, (apply_tick_rate, spawn_player))
+        .add_systems(OnEnter(AppState::Game), (resume_simulation, give_player_health))
+        .add_systems(OnExit(AppState::Game), (pause_simulation, reset_player_states))
+        .add_systems(OnEnter(SimulationState::Paused), freeze_physics)
+        .add_systems(OnEnter(SimulationState::Running), unfreeze_physics)
+        .add_systems(
+            FixedUpdate,
+            (
+                player_movement,
+                knock_player_back.after(player_movement),
+                player_gravity.after(knock_player_back),
+                check_grounded.after(player_gravity),
+                track_falls.after(check_grounded),
+                check_player_death.after(check_grounded),
+                damage_player.after(check_player_death),
+                reset_player_to_spawn.after(damage_player),
+            )
+                .before(PhysicsSet::SyncBackend)
+                .run_if(simulation_running)
+        )
+        .add_systems(Update, toggle_simulation.run_if(in_state(AppState::Game)))
+        .add_systems(
+            Update,
+            flash_player.run_if(
+                in_state(AppState::Game).and_then(in_state(SimulationState::Running))
+            )
+        )
         .run()
 }
 
@@ -74,18 +196,30 @@ pub struct Player {}
 pub struct Jumps {
     pub has_grounded_jump: bool,
     pub is_jumping: bool,
+    // knocked upwards off something, goes up like a jump whether jump's held or not
+    pub is_bouncing: bool,
 }
 
 #[derive(Resource, Default)]
 pub struct JumpTimer {
     pub jump_expire: Stopwatch,
     pub coyote_time: Stopwatch,
+    pub bounce_expire: Stopwatch,
 }
 
 #[derive(Component)]
 pub struct Dash {
     pub has_dash: bool,
     pub is_dashing: bool,
+    // which way the current dash goes, picked when it starts
+    pub direction: Vec2,
+}
+
+// how high the player's been since they last stood on something, for heavy landings
+#[derive(Component)]
+pub struct FallTracker {
+    pub grounded: bool,
+    pub highest: f32,
 }
 
 #[derive(Resource, Default)]
@@ -93,14 +227,74 @@ pub struct DashTimer {
     pub dash_expire: Stopwatch,
 }
 
-#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+// only there in assist mode, hits come off this and knock the player back
+// instead of killing them outright
+#[derive(Component)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+    // seconds before anything can hurt them again, they flash the whole time
+    pub invincible: f32,
+    // which way they're being knocked, -1.0 or 1.0, and for how many more seconds
+    pub knockback: f32,
+    pub knockback_time: f32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Health {
+        Health {
+            current: max,
+            max,
+            invincible: 0.0,
+            knockback: 0.0,
+            knockback_time: 0.0,
+        }
+    }
+}
+
+// anything that hurts the player on contact
+#[derive(Component)]
+pub struct Hazard {
+    pub damage: u32,
+    // middle of it in world space, the player gets knocked away from here
+    pub center: Vec2,
+}
+
+// how much a hazard in a level file takes off when it doesn't say
+pub fn default_damage() -> u32 {
+    1
+}
+
+// where the player goes when they die, set by the level that's loaded
+#[derive(Resource)]
+pub struct PlayerSpawn {
+    pub position: Vec2,
+}
+
+impl Default for PlayerSpawn {
+    fn default() -> PlayerSpawn {
+        PlayerSpawn {
+            position: Vec2::new(WINDOW_WIDTH / 2.0, WINDOW_HEIGHT / 2.0),
+        }
+    }
+}
+
+// simulation ticks a second this session is running at
+#[derive(Resource, Clone, Copy)]
+pub struct TickRate {
+    pub hz: u32,
+}
+
+// direction and gravity change mid-tick, so they're plain resources rather than
+// states that would only switch over between frames
+#[derive(Resource, Debug, Clone, Copy, Eq, PartialEq, Default)]
 pub enum Direction {
     #[default]
     Right,
     Left,
 }
 
-#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+#[derive(Resource, Debug, Clone, Copy, Eq, PartialEq, Default)]
 pub enum GravitySwitch {
     #[default]
     On,
@@ -112,9 +306,21 @@ pub enum AppState {
     #[default]
     MainMenu,
     Game,
+    LevelResults,
     GameOver,
 }
 
+// which screen of the main menu is showing, Closed outside of AppState::MainMenu
+#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum MenuScreen {
+    #[default]
+    Closed,
+    SlotSelect,
+    LevelSelect,
+    Settings,
+    Controls,
+}
+
 #[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
 pub enum SimulationState {
     #[default]
@@ -129,6 +335,16 @@ pub struct GameOver {
     pub score: u32,
 }
 
+#[derive(Event)]
+pub struct PlayerDied {}
+
+// something hurt the player, source is where it was so they get knocked away from it
+#[derive(Event)]
+pub struct PlayerDamaged {
+    pub damage: u32,
+    pub source: Vec2,
+}
+
 // This PlatformBundle uses drawn shapes rather than sprites
 // should be more flexible once it works
 // #[derive(Bundle, Clone)]
@@ -166,167 +382,281 @@ impl PlatformBundle {
 }
 
 // -- SETUP --
-fn spawn_camera(mut commands: Commands, window_query: Query<&Window, With<PrimaryWindow>>) {
-    let window: &Window = window_query.get_single().unwrap();
-
-    commands.spawn(Camera2dBundle {
-        transform: Transform::from_xyz(window.width() / 2.0, window.height() / 2.0, 0.0),
-        ..default()
-    });
-}
-
 fn spawn_player(
     mut commands: Commands,
     window_query: Query<&Window, With<PrimaryWindow>>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>
+    asset_server: Res<AssetServer>,
+    player_animations: Res<PlayerAnimations>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>
 ) {
     let window: &Window = window_query.get_single().unwrap();
+    let spawn_position = Vec2::new(window.width() / 2.0, window.height() / 2.0);
 
     //spawn player
     commands
         .spawn((
-            MaterialMesh2dBundle {
-                // mesh: shapes[0].clone(),
-                mesh: Mesh2dHandle(
-                    meshes.add(Rectangle::new(HALF_PLAYER * 2.0, HALF_PLAYER * 2.0))
-                ),
-                material: materials.add(PLAYER_COLOR),
-                ..default()
-            },
+            SpatialBundle::from_transform(Transform::from_translation(spawn_position.extend(0.0))),
+            Interpolated::new(spawn_position),
             Player {},
             Jumps {
                 has_grounded_jump: false,
                 is_jumping: false,
+                is_bouncing: false,
             },
             Dash {
                 has_dash: false,
                 is_dashing: false,
+                direction: Vec2::X,
+            },
+            FallTracker {
+                grounded: false,
+                highest: spawn_position.y,
             },
             RigidBody::Dynamic,
         ))
         .insert((
-            TransformBundle::from(
-                Transform::from_xyz(window.width() / 2.0, window.height() / 2.0, 0.0)
-            ),
             Collider::cuboid(HALF_PLAYER, HALF_PLAYER),
             LockedAxes::ROTATION_LOCKED,
+            Velocity::zero(),
             GravityScale(0.0),
             Sleeping::disabled(),
             Ccd::enabled(),
             ActiveEvents::COLLISION_EVENTS,
+            ParticleEmitter::new("dash_afterimage"),
             KinematicCharacterController {
                 autostep: None,
                 snap_to_ground: None,
                 ..default()
             },
-        ));
+        ))
+        // drawn on a child so it can be moved between ticks without touching the body
+        .with_children(|parent| {
+            parent.spawn((
+                player_animations.set.sprite_sheet_bundle(
+                    &asset_server,
+                    &mut texture_atlas_layouts
+                ),
+                Animator::default(),
+                SquashStretch::default(),
+                InterpolatedVisual {},
+            ));
+        });
 }
 
-fn spawn_platforms(mut commands: Commands, window_query: Query<&Window, With<PrimaryWindow>>) {
-    let window: &Window = window_query.get_single().unwrap();
+fn player_gravity(
+    time: Res<Time>,
+    mut controllers: Query<&mut KinematicCharacterController>,
+    gravity_switch: Res<GravitySwitch>
+) {
+    if *gravity_switch == GravitySwitch::On {
+        if let Ok(mut controller) = controllers.get_single_mut() {
+            // gravity;
+            let mut translation = controller.translation.unwrap();
+            translation.y -= PLAYER_GRAVITY * time.delta_seconds();
+            controller.translation = Some(translation);
+        }
+    }
+}
 
-    let half_width = window.width() / 2.0;
-    let half_height = window.height() / 2.0;
+fn check_player_death(
+    rapier_context: Res<RapierContext>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    hazard_query: Query<(Entity, &Hazard)>,
+    mut player_died_event_writer: EventWriter<PlayerDied>,
+    mut player_damaged_event_writer: EventWriter<PlayerDamaged>,
+    mut screen_shake_event_writer: EventWriter<ScreenShake>,
+    mut play_sfx_event_writer: EventWriter<PlaySfx>
+) {
+    if let Ok((player, player_position)) = player_query.get_single() {
+        // fell off the bottom of the level, no amount of health saves them from that
+        if player_position.translation.y <= 0.0 {
+            player_died_event_writer.send(PlayerDied {});
+            screen_shake_event_writer.send(ScreenShake { trauma: DEATH_TRAUMA });
+            play_sfx_event_writer.send(PlaySfx(SfxId::Death));
+            return;
+        }
 
-    // spawn platform
-    commands.spawn(PlatformBundle::new(400.0, 40.0, half_width, half_height - 120.0));
+        // touching spikes or anything else that hurts
+        for (hazard_entity, hazard) in hazard_query.iter() {
+            if rapier_context.intersection_pair(player, hazard_entity) == Some(true) {
+                player_damaged_event_writer.send(PlayerDamaged {
+                    damage: hazard.damage,
+                    source: hazard.center,
+                });
+            }
+        }
+    }
+}
 
-    // left platform
-    commands.spawn(PlatformBundle::new(200.0, 20.0, half_width - 300.0, half_height));
+// without health any hit kills, with it hits come off it until it runs out
+fn damage_player(
+    mut player_damaged_event_reader: EventReader<PlayerDamaged>,
+    mut player_query: Query<
+        (&Transform, &mut Jumps, &mut Dash, Option<&mut Health>),
+        With<Player>
+    >,
+    mut jump_timer: ResMut<JumpTimer>,
+    mut dash_timer: ResMut<DashTimer>,
+    mut player_died_event_writer: EventWriter<PlayerDied>,
+    mut screen_shake_event_writer: EventWriter<ScreenShake>,
+    mut hit_stop_event_writer: EventWriter<HitStop>,
+    mut play_sfx_event_writer: EventWriter<PlaySfx>,
+    mut spawn_particles_event_writer: EventWriter<SpawnParticles>
+) {
+    let Ok((transform, mut jumps, mut dash, mut health)) = player_query.get_single_mut() else {
+        player_damaged_event_reader.clear();
+        return;
+    };
+    let position = transform.translation.truncate();
+
+    let mut died = false;
+    for event in player_damaged_event_reader.read() {
+        if died {
+            continue;
+        }
+        let Some(health) = health.as_deref_mut() else {
+            died = true;
+            continue;
+        };
+        // still blinking from the last hit
+        if health.invincible > 0.0 {
+            continue;
+        }
 
-    // right platform
-    commands.spawn(PlatformBundle::new(200.0, 20.0, half_width + 300.0, half_height));
+        health.current = health.current.saturating_sub(event.damage);
+        if health.current == 0 {
+            died = true;
+            continue;
+        }
 
-    // top center platform
-    commands.spawn(PlatformBundle::new(200.0, 20.0, half_width, half_height + 100.0));
+        health.invincible = INVINCIBLE_TIME;
+        health.knockback = if position.x < event.source.x { -1.0 } else { 1.0 };
+        health.knockback_time = KNOCKBACK_TIME;
+        // getting hit knocks them out of whatever they were doing
+        jumps.is_jumping = false;
+        jumps.is_bouncing = false;
+        dash.is_dashing = false;
+        jump_timer.jump_expire.reset();
+        jump_timer.bounce_expire.reset();
+        dash_timer.dash_expire.reset();
+        screen_shake_event_writer.send(ScreenShake { trauma: HURT_TRAUMA });
+        hit_stop_event_writer.send(HitStop { ticks: HURT_HIT_STOP_TICKS });
+        play_sfx_event_writer.send(PlaySfx(SfxId::Hurt));
+    }
 
-    commands.spawn(PlatformBundle::new(500.0, 40.0, half_width + 800.0, half_height - 120.0));
+    if died {
+        player_died_event_writer.send(PlayerDied {});
+        screen_shake_event_writer.send(ScreenShake { trauma: DEATH_TRAUMA });
+        play_sfx_event_writer.send(PlaySfx(SfxId::Death));
+        spawn_particles_event_writer.send(SpawnParticles {
+            effect: "death_burst",
+            position,
+            source: None,
+        });
+    }
 }
 
-fn spawn_spikes(
-    mut commands: Commands,
-    window_query: Query<&Window, With<PrimaryWindow>>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>
+// takes over from the player's input while they're being knocked back
+fn knock_player_back(
+    time: Res<Time>,
+    mut player_query: Query<(&mut KinematicCharacterController, &mut Health), With<Player>>,
+    mut gravity_switch: ResMut<GravitySwitch>
 ) {
-    let window: &Window = window_query.get_single().unwrap();
+    for (mut controller, mut health) in player_query.iter_mut() {
+        health.invincible = (health.invincible - time.delta_seconds()).max(0.0);
+        if health.knockback_time <= 0.0 {
+            continue;
+        }
 
-    // top center spike
-    commands
-        .spawn((
-            MaterialMesh2dBundle {
-                mesh: Mesh2dHandle(
-                    meshes.add(
-                        Triangle2d::new(
-                            Vec2::new(0.0, 0.0),
-                            Vec2::new(50.0, 0.0),
-                            Vec2::new(25.0, 75.0)
-                        )
-                    )
-                ),
-                material: materials.add(SPIKE_COLOR),
-                ..default()
-            },
-            RigidBody::Fixed,
-        ))
-        .insert((
-            TransformBundle::from(
-                Transform::from_xyz(window.width() / 2.0 - 25.0, window.height() / 2.0 + 110.0, 0.0)
-            ),
-            Collider::triangle(Vec2::new(0.0, 0.0), Vec2::new(50.0, 0.0), Vec2::new(25.0, 75.0)),
-            Sensor,
-        ));
+        health.knockback_time -= time.delta_seconds();
+        controller.translation = Some(
+            Vec2::new(health.knockback * KNOCKBACK_SPEED, KNOCKBACK_LIFT) * time.delta_seconds()
+        );
+        *gravity_switch = GravitySwitch::Off;
+    }
 }
 
-fn camera_follow(
-    player_query: Query<&Transform, With<Player>>,
-    mut camera_query: Query<&mut Transform, (With<Camera>, Without<Player>)>
+// blinks while invincible so it's clear the hit landed
+fn flash_player(
+    player_query: Query<(Option<&Health>, &Children), With<Player>>,
+    mut visibility_query: Query<&mut Visibility, With<InterpolatedVisual>>
 ) {
-    if let Ok(player_transform) = player_query.get_single() {
-        let pos = player_transform.translation;
-
-        if let Ok(mut camera_transform) = camera_query.get_single_mut() {
-            camera_transform.translation.x = pos.x;
-            camera_transform.translation.y = pos.y;
+    for (health, children) in player_query.iter() {
+        // health can be taken away mid-blink by turning assist mode off
+        let hidden = health.is_some_and(|health| {
+            health.invincible > 0.0 &&
+                ((health.invincible / INVINCIBLE_FLASH_INTERVAL) as u32) % 2 == 1
+        });
+
+        for &child in children.iter() {
+            if let Ok(mut visibility) = visibility_query.get_mut(child) {
+                *visibility = if hidden { Visibility::Hidden } else { Visibility::Inherited };
+            }
         }
     }
 }
 
-fn player_gravity(
-    mut controllers: Query<&mut KinematicCharacterController>,
-    current_gravity_switch: Res<State<GravitySwitch>>
+fn reset_player_to_spawn(
+    mut player_died_event_reader: EventReader<PlayerDied>,
+    mut player_query: Query<
+        (&mut Transform, &mut Interpolated, &mut FallTracker, Option<&mut Health>),
+        With<Player>
+    >,
+    player_spawn: Res<PlayerSpawn>
 ) {
-    if current_gravity_switch.get() == &GravitySwitch::On {
-        if let Ok(mut controller) = controllers.get_single_mut() {
-            // gravity;
-            let mut translation = controller.translation.unwrap();
-            translation.y -= PLAYER_GRAVITY;
-            controller.translation = Some(translation);
+    if player_died_event_reader.read().last().is_some() {
+        if let Ok((mut player_position, mut interpolated, mut fall_tracker, health)) = player_query
+            .get_single_mut()
+        {
+            player_position.translation = player_spawn.position.extend(0.0);
+            interpolated.snap_to(player_spawn.position);
+            // dropping in at the spawn isn't a heavy landing
+            fall_tracker.highest = player_spawn.position.y;
+            if let Some(mut health) = health {
+                *health = Health::new(health.max);
+            }
         }
     }
 }
 
-fn reset_player_to_spawn(mut player_query: Query<&mut Transform, With<Player>>) {
-    if let Ok(mut player_position) = player_query.get_single_mut() {
-        if player_position.translation.y <= 0.0 {
-            player_position.translation = Vec3::new(WINDOW_WIDTH / 2.0, WINDOW_HEIGHT / 2.0, 0.0);
+// assist mode's health, or whatever the replay being watched was recorded with
+fn give_player_health(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    playback: Option<Res<ReplayPlayback>>,
+    player_query: Query<Entity, With<Player>>
+) {
+    let max = match playback {
+        Some(playback) => playback.replay.assist_health,
+        None => settings.assist_health,
+    };
+
+    for player in player_query.iter() {
+        if max == 0 {
+            commands.entity(player).remove::<Health>();
+        } else {
+            commands.entity(player).insert(Health::new(max));
         }
     }
 }
 
 fn player_movement(
-    keyboard_input: ResMut<ButtonInput<KeyCode>>,
-    mut controllers: Query<(&mut KinematicCharacterController, &mut Jumps, &mut Dash)>,
     time: Res<Time>,
+    tick_actions: Res<TickActions>,
+    mut controllers: Query<(&mut KinematicCharacterController, &mut Jumps, &mut Dash)>,
     mut jump_timer: ResMut<JumpTimer>,
     mut dash_timer: ResMut<DashTimer>,
-    mut next_direction: ResMut<NextState<Direction>>,
-    current_direction: Res<State<Direction>>,
-    mut next_gravity_switch: ResMut<NextState<GravitySwitch>>
+    mut direction: ResMut<Direction>,
+    mut gravity_switch: ResMut<GravitySwitch>,
+    mut screen_shake_event_writer: EventWriter<ScreenShake>,
+    mut hit_stop_event_writer: EventWriter<HitStop>,
+    mut play_sfx_event_writer: EventWriter<PlaySfx>
 ) {
     if let Ok((mut controller, mut jumps, mut dash)) = controllers.get_single_mut() {
-        next_gravity_switch.set(GravitySwitch::On);
+        let action_state = &tick_actions.actions;
+        // time is the fixed clock here, so this is always exactly one tick
+        let tick = time.delta_seconds();
+        *gravity_switch = GravitySwitch::On;
 
         let mut translation: Vec2 = match controller.translation {
             Some(vec) => vec,
@@ -335,61 +665,82 @@ fn player_movement(
 
         if !dash.is_dashing {
             //horizontal
-            if keyboard_input.pressed(KeyCode::ArrowRight) || keyboard_input.pressed(KeyCode::KeyD) {
+            if action_state.pressed(Action::MoveRight) {
                 //right
-                translation.x = PLAYER_SPEED;
-                next_direction.set(Direction::Right);
-            } else if
-                keyboard_input.pressed(KeyCode::ArrowLeft) ||
-                keyboard_input.pressed(KeyCode::KeyA)
-            {
+                translation.x = PLAYER_SPEED * tick;
+                *direction = Direction::Right;
+            } else if action_state.pressed(Action::MoveLeft) {
                 // left
-                translation.x = -PLAYER_SPEED;
-                next_direction.set(Direction::Left);
+                translation.x = -PLAYER_SPEED * tick;
+                *direction = Direction::Left;
             }
 
             // vertical
-            if keyboard_input.pressed(KeyCode::ArrowUp) || keyboard_input.pressed(KeyCode::KeyW) {
+            if action_state.pressed(Action::Up) {
                 // up
-                next_gravity_switch.set(GravitySwitch::Off);
-                translation.y = PLAYER_SPEED;
+                *gravity_switch = GravitySwitch::Off;
+                translation.y = PLAYER_SPEED * tick;
             } else if
                 // down
-                (keyboard_input.pressed(KeyCode::ArrowDown) ||
-                    keyboard_input.pressed(KeyCode::KeyS)) &&
+                action_state.pressed(Action::Down) &&
                 jumps.is_jumping
             {
-                translation.y = -PLAYER_SPEED;
+                translation.y = -PLAYER_SPEED * tick;
             }
 
             // jump
             // if player isn't jumping but can and pressed jump then jump
-            if jumps.has_grounded_jump && keyboard_input.pressed(KeyCode::Space) {
+            if jumps.has_grounded_jump && action_state.pressed(Action::Jump) {
+                if !jumps.is_jumping {
+                    play_sfx_event_writer.send(PlaySfx(SfxId::Jump));
+                }
                 jumps.is_jumping = true;
                 // Set velocity y to jump speed
-                translation.y = JUMP_SPEED;
+                translation.y = JUMP_SPEED * tick;
                 // also turn off gravity during jump
-                next_gravity_switch.set(GravitySwitch::Off);
+                *gravity_switch = GravitySwitch::Off;
             }
 
             // player dash
             // if player isn't currently dashing or jumping, has dash and presses dash key, then dash
-            if
-                dash.has_dash &&
-                (keyboard_input.just_pressed(KeyCode::ShiftLeft) ||
-                    keyboard_input.just_pressed(KeyCode::ShiftRight)) &&
-                !jumps.is_jumping
-            {
+            if dash.has_dash && action_state.just_pressed(Action::Dash) && !jumps.is_jumping {
                 dash.is_dashing = true;
+                // dash where the player's aiming, or straight ahead if they aren't
+                dash.direction = if action_state.aim == Vec2::ZERO {
+                    match *direction {
+                        Direction::Left => Vec2::NEG_X,
+                        Direction::Right => Vec2::X,
+                    }
+                } else {
+                    action_state.aim
+                };
+                screen_shake_event_writer.send(ScreenShake { trauma: DASH_TRAUMA });
+                hit_stop_event_writer.send(HitStop { ticks: DASH_HIT_STOP_TICKS });
+                play_sfx_event_writer.send(PlaySfx(SfxId::Dash));
+                // dashing out of a bounce leaves it behind
+                jumps.is_bouncing = false;
+                jump_timer.bounce_expire.reset();
+            }
+        }
+
+        if jumps.is_bouncing {
+            if jump_timer.bounce_expire.elapsed_secs() < TIME_TO_BOUNCE_EXPIRE {
+                translation.y = BOUNCE_SPEED * tick;
+                *gravity_switch = GravitySwitch::Off;
+                jump_timer.bounce_expire.tick(time.delta());
+            } else {
+                jumps.is_bouncing = false;
+                jump_timer.bounce_expire.reset();
             }
         }
 
         if dash.is_dashing && dash_timer.dash_expire.elapsed_secs() < TIME_TO_DASH_EXPIRE {
-            let direction_mult = match current_direction.get() {
-                Direction::Left => -1.0,
-                Direction::Right => 1.0,
-            };
-            translation.x = direction_mult * DASH_SPEED;
+            translation.x = dash.direction.x * DASH_SPEED * tick;
+            // straight dashes still fall like before, aimed ones hold their line
+            if dash.direction.y != 0.0 {
+                translation.y = dash.direction.y * DASH_SPEED * tick;
+                *gravity_switch = GravitySwitch::Off;
+            }
             dash_timer.dash_expire.tick(time.delta());
         }
 
@@ -402,7 +753,7 @@ fn player_movement(
         // if player stops jumping or jump time expires then stop jumping
         if
             jump_timer.jump_expire.elapsed_secs() >= TIME_TO_JUMP_EXPIRE ||
-            keyboard_input.just_released(KeyCode::Space)
+            action_state.just_released(Action::Jump)
         {
             jumps.is_jumping = false;
             jumps.has_grounded_jump = false;
@@ -420,6 +771,16 @@ fn player_movement(
     }
 }
 
+// knocks the player up off something they came down on, going up like a jump whether
+// jump's held or not, with their dash back
+pub fn bounce_player(jumps: &mut Jumps, dash: &mut Dash, jump_timer: &mut JumpTimer) {
+    jumps.is_bouncing = true;
+    jumps.is_jumping = false;
+    jump_timer.jump_expire.reset();
+    jump_timer.bounce_expire.reset();
+    dash.has_dash = true;
+}
+
 fn check_grounded(
     mut player_query: Query<
         (&KinematicCharacterControllerOutput, &mut Jumps, &mut Dash),
@@ -438,28 +799,119 @@ fn check_grounded(
     }
 }
 
+// squashes the player on landing, and shakes the screen after a long enough drop,
+// harder the further it was
+fn track_falls(
+    time: Res<Time>,
+    mut player_query: Query<
+        (&Transform, &KinematicCharacterControllerOutput, &mut FallTracker, &Children),
+        With<Player>
+    >,
+    mut squash_query: Query<&mut SquashStretch>,
+    mut screen_shake_event_writer: EventWriter<ScreenShake>,
+    mut spawn_particles_event_writer: EventWriter<SpawnParticles>,
+    mut play_sfx_event_writer: EventWriter<PlaySfx>
+) {
+    for (transform, output, mut fall_tracker, children) in player_query.iter_mut() {
+        let y = transform.translation.y;
+
+        if output.grounded && !fall_tracker.grounded {
+            // how fast they were coming down on the tick they hit
+            let impact_speed = (-output.desired_translation.y / time.delta_seconds()).max(0.0);
+            for child in children.iter() {
+                if let Ok(mut squash) = squash_query.get_mut(*child) {
+                    squash.land(impact_speed);
+                }
+            }
+            play_sfx_event_writer.send(PlaySfx(SfxId::Land));
+            if impact_speed >= LANDING_PUFF_SPEED {
+                spawn_particles_event_writer.send(SpawnParticles {
+                    effect: "landing_puff",
+                    position: Vec2::new(transform.translation.x, y - HALF_PLAYER),
+                    source: None,
+                });
+            }
+
+            let drop = fall_tracker.highest - y;
+            if drop >= HEAVY_LANDING_HEIGHT {
+                let trauma = (drop / (HEAVY_LANDING_HEIGHT * 4.0)).min(0.6);
+                screen_shake_event_writer.send(ScreenShake { trauma });
+            }
+        }
+
+        fall_tracker.grounded = output.grounded;
+        fall_tracker.highest = if output.grounded { y } else { fall_tracker.highest.max(y) };
+    }
+}
+
 // -- GAME STATES--
 
-// fn pause_simulation(mut next_simulation_state: ResMut<NextState<SimulationState>>) {
-//     next_simulation_state.set(SimulationState::Paused)
-// }
+// gameplay ticks only in a running level outside of hit-stop, and stops as soon as
+// something asks to leave it. state changes wait for the next frame, which could
+// otherwise be several ticks away
+fn simulation_running(
+    app_state: Res<State<AppState>>,
+    next_app_state: Res<NextState<AppState>>,
+    simulation_state: Res<State<SimulationState>>,
+    hit_stop_timer: Res<HitStopTimer>
+) -> bool {
+    *app_state.get() == AppState::Game &&
+        next_app_state.0.is_none() &&
+        *simulation_state.get() == SimulationState::Running &&
+        !hit_stop_timer.frozen
+}
 
-// fn resume_simulation(mut next_simulation_state: ResMut<NextState<SimulationState>>) {
-//     next_simulation_state.set(SimulationState::Running)
-// }
+fn pause_simulation(mut next_simulation_state: ResMut<NextState<SimulationState>>) {
+    next_simulation_state.set(SimulationState::Paused)
+}
 
-// fn toggle_simulation(
-//     keyboard_input: Res<ButtonInput<KeyCode>>,
-//     current_simulation_state: Res<State<SimulationState>>,
-//     mut next_simulation_state: ResMut<NextState<SimulationState>>
-// ) {
-//     if keyboard_input.just_pressed(KeyCode::KeyP) {
-//         match current_simulation_state.get() {
-//             SimulationState::Paused => { next_simulation_state.set(SimulationState::Running) }
-//             SimulationState::Running => { next_simulation_state.set(SimulationState::Paused) }
-//         }
-//     }
-// }
+fn resume_simulation(mut next_simulation_state: ResMut<NextState<SimulationState>>) {
+    next_simulation_state.set(SimulationState::Running)
+}
+
+fn toggle_simulation(
+    action_state: Res<ActionState>,
+    current_simulation_state: Res<State<SimulationState>>,
+    mut next_simulation_state: ResMut<NextState<SimulationState>>
+) {
+    if action_state.just_pressed(Action::Pause) {
+        match current_simulation_state.get() {
+            SimulationState::Paused => { next_simulation_state.set(SimulationState::Running) }
+            SimulationState::Running => { next_simulation_state.set(SimulationState::Paused) }
+        }
+    }
+}
+
+// stop rapier too so nothing keeps moving under the pause menu
+fn freeze_physics(mut rapier_config: ResMut<RapierConfiguration>) {
+    rapier_config.physics_pipeline_active = false;
+}
+
+fn unfreeze_physics(mut rapier_config: ResMut<RapierConfiguration>) {
+    rapier_config.physics_pipeline_active = true;
+}
+
+// so every level starts the player off the same way, whatever happened in the last one
+fn reset_player_states(
+    mut direction: ResMut<Direction>,
+    mut gravity_switch: ResMut<GravitySwitch>
+) {
+    *direction = Direction::default();
+    *gravity_switch = GravitySwitch::default();
+}
+
+// FixedUpdate runs once per tick, and rapier takes exactly one step of one tick each time
+fn apply_tick_rate(
+    tick_rate: Res<TickRate>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+    mut rapier_config: ResMut<RapierConfiguration>
+) {
+    fixed_time.set_timestep_hz(tick_rate.hz as f64);
+    rapier_config.timestep_mode = TimestepMode::Fixed {
+        dt: 1.0 / (tick_rate.hz as f32),
+        substeps: 1,
+    };
+}
 
 // fn transition_to_game_state(
 //     keyboard_input: Res<ButtonInput<KeyCode>>,
@@ -492,19 +944,6 @@ fn check_grounded(
 //     next_app_state.set(AppState::GameOver);
 // }
 
-// -- EXIT GAME --
-fn exit_game(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut app_exit_event_writer: EventWriter<AppExit>
-) {
-    if
-        keyboard_input.just_pressed(KeyCode::Backspace) ||
-        keyboard_input.just_pressed(KeyCode::Escape)
-    {
-        app_exit_event_writer.send(AppExit);
-    }
-}
-
 // fn jump(
 //     mut player_query: Query<(&mut KinematicCharacterController, &mut Jumps)>,
 //     mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
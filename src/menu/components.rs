@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+
+// -- COMPONENTS --
+
+// prompt text with a keyboard and a gamepad version, swapped by update_device_prompts
+#[derive(Component)]
+pub struct DevicePrompt {
+    pub keyboard: &'static str,
+    pub gamepad: &'static str,
+}
+
+#[derive(Component)]
+pub struct SlotSelectMenu {}
+
+// one row in the slot picker, index into the save slots
+#[derive(Component)]
+pub struct SlotSelectEntry {
+    pub index: usize,
+}
+
+// the line under the slots that says what the keys do, or asks for confirmation
+#[derive(Component)]
+pub struct SlotSelectPrompt {}
+
+#[derive(Component)]
+pub struct LevelSelectMenu {}
+
+// one row in the level select list, index into Campaign.levels
+#[derive(Component)]
+pub struct LevelSelectEntry {
+    pub index: usize,
+}
+
+#[derive(Component)]
+pub struct SettingsMenuRoot {}
+
+// one row in the settings menu, index into SETTINGS_ROWS
+#[derive(Component)]
+pub struct SettingsEntry {
+    pub index: usize,
+}
+
+#[derive(Component)]
+pub struct ControlsMenuRoot {}
+
+// one row in the controls menu, index into ACTIONS
+#[derive(Component)]
+pub struct ControlsEntry {
+    pub index: usize,
+}
+
+// the line under the bindings, changes while waiting for a key
+#[derive(Component)]
+pub struct ControlsPrompt {}
+
+#[derive(Component)]
+pub struct PauseMenuRoot {}
+
+// one row in the pause menu, index into PAUSE_ROWS
+#[derive(Component)]
+pub struct PauseEntry {
+    pub index: usize,
+}
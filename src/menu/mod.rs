@@ -0,0 +1,91 @@
+// import bevy crates
+use bevy::prelude::*;
+
+// import modules
+pub mod components;
+pub mod resources;
+pub mod systems;
+
+// use modules
+use resources::*;
+use systems::*;
+use crate::{ AppState, MenuScreen, SimulationState };
+
+pub const MENU_BACKGROUND_COLOR: Color = Color::rgb(0.1, 0.1, 0.12);
+pub const SELECTED_COLOR: Color = Color::GOLD;
+pub const UNSELECTED_COLOR: Color = Color::WHITE;
+pub const LOCKED_COLOR: Color = Color::DARK_GRAY;
+// see-through so the level still shows behind the pause menu
+pub const PAUSE_BACKGROUND_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.6);
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LevelSelection>()
+            .init_resource::<SlotMenu>()
+            .init_resource::<SettingsMenu>()
+            .init_resource::<ControlsMenu>()
+            .init_resource::<PauseMenu>()
+            .add_systems(OnEnter(AppState::MainMenu), open_menu)
+            .add_systems(OnExit(AppState::MainMenu), close_menu)
+            .add_systems(Update, update_device_prompts)
+            .add_systems(
+                Update,
+                play_menu_sounds.run_if(
+                    not(in_state(MenuScreen::Closed)).or_else(
+                        in_state(AppState::Game).and_then(in_state(SimulationState::Paused))
+                    )
+                )
+            )
+            // slot select
+            .add_systems(OnEnter(MenuScreen::SlotSelect), spawn_slot_select)
+            .add_systems(OnExit(MenuScreen::SlotSelect), despawn_slot_select)
+            .add_systems(
+                Update,
+                (slot_select_input, update_slot_select_entries.after(slot_select_input)).run_if(
+                    in_state(MenuScreen::SlotSelect)
+                )
+            )
+            // level select
+            .add_systems(OnEnter(MenuScreen::LevelSelect), spawn_level_select)
+            .add_systems(OnExit(MenuScreen::LevelSelect), despawn_level_select)
+            .add_systems(
+                Update,
+                (level_select_input, update_level_select_entries.after(level_select_input)).run_if(
+                    in_state(MenuScreen::LevelSelect)
+                )
+            )
+            // settings
+            .add_systems(OnEnter(MenuScreen::Settings), spawn_settings_menu)
+            .add_systems(OnExit(MenuScreen::Settings), despawn_settings_menu)
+            .add_systems(
+                Update,
+                (settings_menu_input, update_settings_entries.after(settings_menu_input)).run_if(
+                    in_state(MenuScreen::Settings)
+                )
+            )
+            // controls
+            .add_systems(OnEnter(MenuScreen::Controls), spawn_controls_menu)
+            .add_systems(OnExit(MenuScreen::Controls), despawn_controls_menu)
+            .add_systems(
+                Update,
+                (controls_menu_input, update_controls_entries.after(controls_menu_input)).run_if(
+                    in_state(MenuScreen::Controls)
+                )
+            )
+            // pause, only over a level that's being played
+            .add_systems(
+                OnEnter(SimulationState::Paused),
+                spawn_pause_menu.run_if(in_state(AppState::Game))
+            )
+            .add_systems(OnExit(SimulationState::Paused), despawn_pause_menu)
+            .add_systems(OnExit(AppState::Game), despawn_pause_menu)
+            .add_systems(
+                Update,
+                (pause_menu_input, update_pause_entries.after(pause_menu_input)).run_if(
+                    in_state(AppState::Game).and_then(in_state(SimulationState::Paused))
+                )
+            );
+    }
+}
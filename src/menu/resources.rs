@@ -0,0 +1,177 @@
+use bevy::prelude::*;
+
+use crate::controls::resources::{ Action, Binding };
+
+use crate::save::file::load_slot;
+use crate::save::resources::SlotError;
+use crate::save::SLOT_COUNT;
+use crate::MenuScreen;
+
+// -- RESOURCES --
+
+// which level select row is highlighted, kept around so the menu
+// reopens on the last level that was picked
+#[derive(Resource, Default)]
+pub struct LevelSelection {
+    pub index: usize,
+}
+
+#[derive(Resource, Default)]
+pub struct SlotMenu {
+    pub selection: usize,
+    pub mode: SlotMenuMode,
+    // what's in each slot, reloaded whenever slots get copied or deleted
+    pub slots: Vec<SlotSummary>,
+}
+
+impl SlotMenu {
+    pub fn refresh(&mut self) {
+        self.slots = (0..SLOT_COUNT)
+            .map(|index| {
+                match load_slot(index) {
+                    Ok(Some(slot)) =>
+                        SlotSummary::Saved {
+                            playtime: slot.playtime,
+                            completion: slot.completion,
+                            last_played: slot.last_played,
+                        },
+                    Ok(None) => SlotSummary::Empty,
+                    Err(SlotError::Newer(_)) => SlotSummary::Newer,
+                    Err(SlotError::Unreadable(_)) => SlotSummary::Unreadable,
+                }
+            })
+            .collect();
+    }
+}
+
+// copying and deleting both need a second keypress to go through
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum SlotMenuMode {
+    #[default]
+    Browse,
+    PickCopyTarget {
+        source: usize,
+    },
+    ConfirmCopy {
+        source: usize,
+        target: usize,
+    },
+    ConfirmDelete {
+        index: usize,
+    },
+    // tried to play a slot saved by a newer build, which is left alone
+    CantOpen {
+        index: usize,
+    },
+}
+
+pub enum SlotSummary {
+    Empty,
+    Unreadable,
+    // saved by a newer build
+    Newer,
+    Saved {
+        playtime: f32,
+        completion: f32,
+        last_played: u64,
+    },
+}
+
+#[derive(Resource)]
+pub struct SettingsMenu {
+    pub selection: usize,
+    // the screen settings was opened from, and goes back to
+    pub return_to: MenuScreen,
+}
+
+impl Default for SettingsMenu {
+    fn default() -> SettingsMenu {
+        SettingsMenu {
+            selection: 0,
+            return_to: MenuScreen::SlotSelect,
+        }
+    }
+}
+
+// rows of the settings menu, top to bottom
+pub const SETTINGS_ROWS: [SettingsRow; 13] = [
+    SettingsRow::WindowMode,
+    SettingsRow::Resolution,
+    SettingsRow::Vsync,
+    SettingsRow::MasterVolume,
+    SettingsRow::MusicVolume,
+    SettingsRow::SfxVolume,
+    SettingsRow::ScreenShake,
+    SettingsRow::DebugRender,
+    SettingsRow::Ghost,
+    SettingsRow::SpeedrunTimer,
+    SettingsRow::AssistHealth,
+    SettingsRow::StickDeadzone,
+    SettingsRow::Controls,
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SettingsRow {
+    WindowMode,
+    Resolution,
+    Vsync,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    ScreenShake,
+    DebugRender,
+    Ghost,
+    SpeedrunTimer,
+    AssistHealth,
+    StickDeadzone,
+    Controls,
+}
+
+#[derive(Resource, Default)]
+pub struct ControlsMenu {
+    pub selection: usize,
+    pub mode: ControlsMenuMode,
+}
+
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum ControlsMenuMode {
+    #[default]
+    Browse,
+    // waiting for the next key to bind to the selected action
+    Capture,
+    // the key is already used by another action, asks before moving it over
+    ConfirmConflict {
+        binding: Binding,
+        other: Action,
+    },
+    // the key is all a required action has left, so it waits for a different one
+    LastBinding {
+        binding: Binding,
+        other: Action,
+    },
+}
+
+#[derive(Resource, Default)]
+pub struct PauseMenu {
+    pub selection: usize,
+}
+
+// rows of the pause menu, top to bottom
+pub const PAUSE_ROWS: [PauseRow; 3] = [PauseRow::Resume, PauseRow::QuitToMenu, PauseRow::QuitGame];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PauseRow {
+    Resume,
+    QuitToMenu,
+    QuitGame,
+}
+
+impl PauseRow {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PauseRow::Resume => "Resume",
+            PauseRow::QuitToMenu => "Quit to menu",
+            PauseRow::QuitGame => "Quit game",
+        }
+    }
+}
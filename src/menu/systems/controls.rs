@@ -0,0 +1,219 @@
+use bevy::prelude::*;
+
+use crate::controls::resources::{ ActionState, Binding, InputDevice, InputMap, ACTIONS };
+use crate::menu::components::*;
+use crate::menu::resources::*;
+use crate::menu::systems::MenuInput;
+use crate::menu::{ MENU_BACKGROUND_COLOR, SELECTED_COLOR, UNSELECTED_COLOR };
+use crate::save::resources::SaveRequested;
+use crate::settings::resources::Settings;
+use crate::MenuScreen;
+
+// -- CONTROLS --
+
+pub fn spawn_controls_menu(mut commands: Commands, mut controls_menu: ResMut<ControlsMenu>) {
+    controls_menu.selection = 0;
+    controls_menu.mode = ControlsMenuMode::Browse;
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.0),
+                    ..default()
+                },
+                background_color: MENU_BACKGROUND_COLOR.into(),
+                ..default()
+            },
+            ControlsMenuRoot {},
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section("Controls", TextStyle {
+                    font_size: 48.0,
+                    ..default()
+                })
+            );
+
+            // text gets filled in by update_controls_entries
+            for index in 0..ACTIONS.len() {
+                parent.spawn((
+                    TextBundle::from_section("", TextStyle { font_size: 28.0, ..default() }),
+                    ControlsEntry { index },
+                ));
+            }
+
+            parent.spawn((
+                TextBundle::from_section("", TextStyle { font_size: 20.0, ..default() }),
+                ControlsPrompt {},
+            ));
+        });
+}
+
+pub fn despawn_controls_menu(
+    mut commands: Commands,
+    menu_query: Query<Entity, With<ControlsMenuRoot>>
+) {
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn controls_menu_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    action_state: Res<ActionState>,
+    mut controls_menu: ResMut<ControlsMenu>,
+    mut settings: ResMut<Settings>,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
+    mut save_requested_event_writer: EventWriter<SaveRequested>
+) {
+    let action = ACTIONS[controls_menu.selection];
+
+    match controls_menu.mode {
+        ControlsMenuMode::Browse => {
+            let menu_input = MenuInput::read(&action_state, &keyboard_input, &gamepad_buttons);
+            controls_menu.selection = menu_input.scroll(controls_menu.selection, ACTIONS.len());
+
+            if menu_input.confirm {
+                controls_menu.mode = ControlsMenuMode::Capture;
+            } else if
+                keyboard_input.just_pressed(KeyCode::Backspace) ||
+                keyboard_input.just_pressed(KeyCode::Delete) ||
+                menu_input.tertiary
+            {
+                // required actions can be rebound but never left empty
+                if !action.is_required() {
+                    settings.bindings.clear(action);
+                }
+            } else if keyboard_input.just_pressed(KeyCode::KeyR) || menu_input.options {
+                settings.bindings = InputMap::default();
+            } else if menu_input.back {
+                save_requested_event_writer.send(SaveRequested {});
+                next_menu_screen.set(MenuScreen::Settings);
+            }
+        }
+        ControlsMenuMode::Capture | ControlsMenuMode::LastBinding { .. } => {
+            // escape and select always cancel, so they can't be bound from here
+            let cancel_pressed = gamepad_buttons
+                .get_just_pressed()
+                .any(|button| button.button_type == GamepadButtonType::Select);
+            if keyboard_input.just_pressed(KeyCode::Escape) || cancel_pressed {
+                controls_menu.mode = ControlsMenuMode::Browse;
+                return;
+            }
+
+            // only buttons can be picked up here, the stick keeps its default bindings
+            let captured = keyboard_input
+                .get_just_pressed()
+                .next()
+                .map(|key| Binding::Key(*key))
+                .or_else(|| {
+                    gamepad_buttons
+                        .get_just_pressed()
+                        .next()
+                        .map(|button| Binding::GamepadButton(button.button_type))
+                });
+
+            if let Some(binding) = captured {
+                controls_menu.mode = match settings.bindings.action_for(binding) {
+                    // already bound to this action, nothing to do
+                    Some(other) if other == action => ControlsMenuMode::Browse,
+                    Some(other) if settings.bindings.is_last_binding(other) => {
+                        ControlsMenuMode::LastBinding { binding, other }
+                    }
+                    Some(other) => ControlsMenuMode::ConfirmConflict { binding, other },
+                    None => {
+                        settings.bindings.bind(action, binding);
+                        ControlsMenuMode::Browse
+                    }
+                };
+            }
+        }
+        ControlsMenuMode::ConfirmConflict { binding, .. } => {
+            let menu_input = MenuInput::read(&action_state, &keyboard_input, &gamepad_buttons);
+            if keyboard_input.just_pressed(KeyCode::KeyY) || menu_input.confirm {
+                settings.bindings.bind(action, binding);
+                controls_menu.mode = ControlsMenuMode::Browse;
+            } else if keyboard_input.just_pressed(KeyCode::KeyN) || menu_input.back {
+                controls_menu.mode = ControlsMenuMode::Browse;
+            }
+        }
+    }
+}
+
+pub fn update_controls_entries(
+    settings: Res<Settings>,
+    controls_menu: Res<ControlsMenu>,
+    input_device: Res<InputDevice>,
+    mut entry_query: Query<(&mut Text, &ControlsEntry)>,
+    mut prompt_query: Query<&mut Text, (With<ControlsPrompt>, Without<ControlsEntry>)>
+) {
+    for (mut text, entry) in entry_query.iter_mut() {
+        let action = ACTIONS[entry.index];
+        let bindings = settings.bindings.bindings_for(action);
+        let binding_names = if bindings.is_empty() {
+            "unbound".to_string()
+        } else {
+            bindings
+                .iter()
+                .map(|binding| binding.name())
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+
+        text.sections[0].value = format!("{}    {}", action.name(), binding_names);
+        text.sections[0].style.color = if entry.index == controls_menu.selection {
+            SELECTED_COLOR
+        } else {
+            UNSELECTED_COLOR
+        };
+    }
+
+    if let Ok(mut text) = prompt_query.get_single_mut() {
+        let action = ACTIONS[controls_menu.selection];
+        text.sections[0].value = match controls_menu.mode {
+            ControlsMenuMode::Browse if action.is_required() =>
+                input_device
+                    .prompt(
+                        "Enter to add a key, R to reset all, Esc to go back",
+                        "A to add a button, Select to reset all, B to save and go back"
+                    )
+                    .to_string(),
+            ControlsMenuMode::Browse =>
+                input_device
+                    .prompt(
+                        "Enter to add a key, Backspace to clear, R to reset all, Esc to go back",
+                        "A to add a button, Y to clear, Select to reset all, B to save and go back"
+                    )
+                    .to_string(),
+            ControlsMenuMode::Capture =>
+                format!(
+                    "Press a key or button for {}, {} to cancel",
+                    action.name(),
+                    input_device.prompt("Esc", "Select")
+                ),
+            ControlsMenuMode::ConfirmConflict { binding, other } =>
+                format!(
+                    "{} is already used for {}. Move it to {}? {}",
+                    binding.name(),
+                    other.name(),
+                    action.name(),
+                    input_device.prompt("Y / N", "A / B")
+                ),
+            ControlsMenuMode::LastBinding { binding, other } =>
+                format!(
+                    "{} is the only binding {} has left. Press something else for {}, {} to cancel",
+                    binding.name(),
+                    other.name(),
+                    action.name(),
+                    input_device.prompt("Esc", "Select")
+                ),
+        };
+    }
+}
@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+
+use crate::controls::resources::ActionState;
+use crate::level::resources::{ Campaign, CampaignProgress, CurrentLevel };
+use crate::level::systems::format_time;
+use crate::menu::components::*;
+use crate::menu::resources::*;
+use crate::menu::systems::MenuInput;
+use crate::menu::{ LOCKED_COLOR, MENU_BACKGROUND_COLOR, SELECTED_COLOR, UNSELECTED_COLOR };
+use crate::save::resources::SaveRequested;
+use crate::{ AppState, MenuScreen };
+
+// -- LEVEL SELECT --
+
+pub fn spawn_level_select(
+    mut commands: Commands,
+    campaign: Res<Campaign>,
+    progress: Res<CampaignProgress>
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.0),
+                    ..default()
+                },
+                background_color: MENU_BACKGROUND_COLOR.into(),
+                ..default()
+            },
+            LevelSelectMenu {},
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section("Select Level", TextStyle {
+                    font_size: 48.0,
+                    ..default()
+                })
+            );
+
+            for (index, entry) in campaign.levels.iter().enumerate() {
+                let text = if progress.is_unlocked(&campaign, index) {
+                    let level_progress = progress.level(&entry.id);
+                    let best_time = match level_progress.best_time {
+                        Some(time) => format_time(time),
+                        None => "--:--".to_string(),
+                    };
+
+                    format!(
+                        "{}. {}    best {}    deaths {}    collectibles {}/{}",
+                        index + 1,
+                        entry.name,
+                        best_time,
+                        level_progress.deaths,
+                        level_progress.collectibles,
+                        entry.total_collectibles
+                    )
+                } else {
+                    format!("{}. {}    [locked]", index + 1, entry.name)
+                };
+
+                parent.spawn((
+                    TextBundle::from_section(text, TextStyle { font_size: 28.0, ..default() }),
+                    LevelSelectEntry { index },
+                ));
+            }
+
+            parent.spawn((
+                TextBundle::from_section("", TextStyle { font_size: 20.0, ..default() }),
+                DevicePrompt {
+                    keyboard: "Up/Down to choose, Enter to play, O for settings, Esc for saves",
+                    gamepad: "A to play, Select for settings, B for saves",
+                },
+            ));
+        });
+}
+
+pub fn despawn_level_select(
+    mut commands: Commands,
+    menu_query: Query<Entity, With<LevelSelectMenu>>
+) {
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn level_select_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    action_state: Res<ActionState>,
+    campaign: Res<Campaign>,
+    progress: Res<CampaignProgress>,
+    mut level_selection: ResMut<LevelSelection>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut settings_menu: ResMut<SettingsMenu>,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
+    mut save_requested_event_writer: EventWriter<SaveRequested>
+) {
+    let menu_input = MenuInput::read(&action_state, &keyboard_input, &gamepad_buttons);
+    level_selection.index = menu_input.scroll(level_selection.index, campaign.levels.len());
+
+    // launch straight into the level, locked ones just don't respond
+    if menu_input.confirm && progress.is_unlocked(&campaign, level_selection.index) {
+        current_level.index = level_selection.index;
+        next_app_state.set(AppState::Game);
+    } else if menu_input.back {
+        // back out to the slot picker, saving so the playtime sticks
+        save_requested_event_writer.send(SaveRequested {});
+        next_menu_screen.set(MenuScreen::SlotSelect);
+    } else if keyboard_input.just_pressed(KeyCode::KeyO) || menu_input.options {
+        settings_menu.return_to = MenuScreen::LevelSelect;
+        next_menu_screen.set(MenuScreen::Settings);
+    }
+}
+
+pub fn update_level_select_entries(
+    campaign: Res<Campaign>,
+    progress: Res<CampaignProgress>,
+    level_selection: Res<LevelSelection>,
+    mut entry_query: Query<(&mut Text, &LevelSelectEntry)>
+) {
+    for (mut text, entry) in entry_query.iter_mut() {
+        let color = if entry.index == level_selection.index {
+            SELECTED_COLOR
+        } else if progress.is_unlocked(&campaign, entry.index) {
+            UNSELECTED_COLOR
+        } else {
+            LOCKED_COLOR
+        };
+
+        for section in text.sections.iter_mut() {
+            section.style.color = color;
+        }
+    }
+}
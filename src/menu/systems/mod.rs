@@ -0,0 +1,126 @@
+use bevy::prelude::*;
+
+use crate::controls::resources::{ Action, ActionState, InputDevice };
+use crate::menu::components::DevicePrompt;
+use crate::save::resources::ActiveSlot;
+use crate::sound::resources::{ PlaySfx, SfxId };
+use crate::MenuScreen;
+
+// each menu screen gets its own file
+pub mod controls;
+pub mod level_select;
+pub mod pause;
+pub mod settings;
+pub mod slot_select;
+
+pub use controls::*;
+pub use level_select::*;
+pub use pause::*;
+pub use settings::*;
+pub use slot_select::*;
+
+// -- MENU SCREENS --
+
+// coming back from a level goes straight to level select, otherwise pick a slot first
+pub fn open_menu(active_slot: Res<ActiveSlot>, mut next_menu_screen: ResMut<NextState<MenuScreen>>) {
+    if active_slot.index.is_some() {
+        next_menu_screen.set(MenuScreen::LevelSelect);
+    } else {
+        next_menu_screen.set(MenuScreen::SlotSelect);
+    }
+}
+
+pub fn close_menu(mut next_menu_screen: ResMut<NextState<MenuScreen>>) {
+    next_menu_screen.set(MenuScreen::Closed);
+}
+
+// -- NAVIGATION --
+
+// what the menus care about this frame. it goes through actions so rebinding and
+// gamepads carry over, but enter and escape always work as confirm and back so a
+// bad set of bindings can't lock anyone out of the menus
+pub struct MenuInput {
+    pub up: bool,
+    pub down: bool,
+    pub left: bool,
+    pub right: bool,
+    pub confirm: bool,
+    pub back: bool,
+    // the other two face buttons and select, for menu shortcuts on a gamepad
+    pub secondary: bool,
+    pub tertiary: bool,
+    pub options: bool,
+}
+
+impl MenuInput {
+    pub fn read(
+        action_state: &ActionState,
+        keyboard_input: &ButtonInput<KeyCode>,
+        gamepad_buttons: &ButtonInput<GamepadButton>
+    ) -> MenuInput {
+        let pad_pressed = |button_type: GamepadButtonType| {
+            gamepad_buttons.get_just_pressed().any(|button| button.button_type == button_type)
+        };
+
+        MenuInput {
+            up: action_state.just_pressed(Action::Up),
+            down: action_state.just_pressed(Action::Down),
+            left: action_state.just_pressed(Action::MoveLeft),
+            right: action_state.just_pressed(Action::MoveRight),
+            confirm: action_state.just_pressed(Action::Jump) ||
+            keyboard_input.just_pressed(KeyCode::Enter) ||
+            pad_pressed(GamepadButtonType::South),
+            back: action_state.just_pressed(Action::Pause) ||
+            keyboard_input.just_pressed(KeyCode::Escape) ||
+            pad_pressed(GamepadButtonType::East),
+            secondary: pad_pressed(GamepadButtonType::West),
+            tertiary: pad_pressed(GamepadButtonType::North),
+            options: pad_pressed(GamepadButtonType::Select),
+        }
+    }
+
+    // moves a list selection up or down, wrapping at the ends
+    pub fn scroll(&self, selection: usize, len: usize) -> usize {
+        if self.up {
+            (selection + len - 1) % len
+        } else if self.down {
+            (selection + 1) % len
+        } else {
+            selection
+        }
+    }
+}
+
+// one place for every menu's sounds, going off the same input the menus read
+pub fn play_menu_sounds(
+    action_state: Res<ActionState>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut play_sfx_event_writer: EventWriter<PlaySfx>
+) {
+    let input = MenuInput::read(&action_state, &keyboard_input, &gamepad_buttons);
+
+    if input.confirm {
+        play_sfx_event_writer.send(PlaySfx(SfxId::MenuSelect));
+    } else if input.back {
+        play_sfx_event_writer.send(PlaySfx(SfxId::MenuBack));
+    } else if input.up || input.down || input.left || input.right {
+        play_sfx_event_writer.send(PlaySfx(SfxId::MenuMove));
+    }
+}
+
+// -- PROMPTS --
+
+// fills in prompts that only change with the input device, the menus with
+// prompts that change as you use them handle the device themselves
+pub fn update_device_prompts(
+    input_device: Res<InputDevice>,
+    mut prompt_query: Query<(&mut Text, Ref<DevicePrompt>)>
+) {
+    for (mut text, prompt) in prompt_query.iter_mut() {
+        if input_device.is_changed() || prompt.is_added() {
+            let value = input_device.prompt(prompt.keyboard, prompt.gamepad);
+            text.sections[0].value = value.to_string();
+        }
+    }
+}
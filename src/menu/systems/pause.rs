@@ -0,0 +1,103 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+
+use crate::controls::resources::ActionState;
+use crate::menu::components::*;
+use crate::menu::resources::*;
+use crate::menu::systems::MenuInput;
+use crate::menu::{ PAUSE_BACKGROUND_COLOR, SELECTED_COLOR, UNSELECTED_COLOR };
+use crate::save::resources::SaveRequested;
+use crate::{ AppState, SimulationState };
+
+// -- PAUSE --
+
+pub fn spawn_pause_menu(mut commands: Commands, mut pause_menu: ResMut<PauseMenu>) {
+    pause_menu.selection = 0;
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.0),
+                    ..default()
+                },
+                background_color: PAUSE_BACKGROUND_COLOR.into(),
+                ..default()
+            },
+            PauseMenuRoot {},
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section("Paused", TextStyle {
+                    font_size: 48.0,
+                    ..default()
+                })
+            );
+
+            for (index, row) in PAUSE_ROWS.iter().enumerate() {
+                parent.spawn((
+                    TextBundle::from_section(row.name(), TextStyle {
+                        font_size: 28.0,
+                        ..default()
+                    }),
+                    PauseEntry { index },
+                ));
+            }
+        });
+}
+
+pub fn despawn_pause_menu(mut commands: Commands, menu_query: Query<Entity, With<PauseMenuRoot>>) {
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// pressing pause again to resume is handled by toggle_simulation
+pub fn pause_menu_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    action_state: Res<ActionState>,
+    mut pause_menu: ResMut<PauseMenu>,
+    mut next_simulation_state: ResMut<NextState<SimulationState>>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut save_requested_event_writer: EventWriter<SaveRequested>,
+    mut app_exit_event_writer: EventWriter<AppExit>
+) {
+    let menu_input = MenuInput::read(&action_state, &keyboard_input, &gamepad_buttons);
+    pause_menu.selection = menu_input.scroll(pause_menu.selection, PAUSE_ROWS.len());
+
+    if menu_input.confirm {
+        match PAUSE_ROWS[pause_menu.selection] {
+            PauseRow::Resume => {
+                next_simulation_state.set(SimulationState::Running);
+            }
+            PauseRow::QuitToMenu => {
+                save_requested_event_writer.send(SaveRequested {});
+                next_app_state.set(AppState::MainMenu);
+            }
+            PauseRow::QuitGame => {
+                // keep the playtime from this session
+                save_requested_event_writer.send(SaveRequested {});
+                app_exit_event_writer.send(AppExit);
+            }
+        }
+    }
+}
+
+pub fn update_pause_entries(
+    pause_menu: Res<PauseMenu>,
+    mut entry_query: Query<(&mut Text, &PauseEntry)>
+) {
+    for (mut text, entry) in entry_query.iter_mut() {
+        text.sections[0].style.color = if entry.index == pause_menu.selection {
+            SELECTED_COLOR
+        } else {
+            UNSELECTED_COLOR
+        };
+    }
+}
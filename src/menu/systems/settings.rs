@@ -0,0 +1,236 @@
+use bevy::prelude::*;
+
+use crate::controls::resources::ActionState;
+use crate::menu::components::*;
+use crate::menu::resources::*;
+use crate::menu::systems::MenuInput;
+use crate::menu::{ MENU_BACKGROUND_COLOR, SELECTED_COLOR, UNSELECTED_COLOR };
+use crate::save::resources::SaveRequested;
+use crate::settings::resources::{
+    Settings,
+    WindowModeSetting,
+    MAX_ASSIST_HEALTH,
+    RESOLUTIONS,
+};
+use crate::MenuScreen;
+
+// -- SETTINGS --
+
+pub fn spawn_settings_menu(mut commands: Commands, mut settings_menu: ResMut<SettingsMenu>) {
+    settings_menu.selection = 0;
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.0),
+                    ..default()
+                },
+                background_color: MENU_BACKGROUND_COLOR.into(),
+                ..default()
+            },
+            SettingsMenuRoot {},
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section("Settings", TextStyle {
+                    font_size: 48.0,
+                    ..default()
+                })
+            );
+
+            // text gets filled in by update_settings_entries
+            for index in 0..SETTINGS_ROWS.len() {
+                parent.spawn((
+                    TextBundle::from_section("", TextStyle { font_size: 28.0, ..default() }),
+                    SettingsEntry { index },
+                ));
+            }
+
+            parent.spawn((
+                TextBundle::from_section("", TextStyle { font_size: 20.0, ..default() }),
+                DevicePrompt {
+                    keyboard: "Up/Down to choose, Left/Right to change, Esc to save and go back",
+                    gamepad: "Left/Right to change, B to save and go back",
+                },
+            ));
+        });
+}
+
+pub fn despawn_settings_menu(
+    mut commands: Commands,
+    menu_query: Query<Entity, With<SettingsMenuRoot>>
+) {
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn settings_menu_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    action_state: Res<ActionState>,
+    mut settings_menu: ResMut<SettingsMenu>,
+    mut settings: ResMut<Settings>,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
+    mut save_requested_event_writer: EventWriter<SaveRequested>
+) {
+    let menu_input = MenuInput::read(&action_state, &keyboard_input, &gamepad_buttons);
+    settings_menu.selection = menu_input.scroll(settings_menu.selection, SETTINGS_ROWS.len());
+    let row = SETTINGS_ROWS[settings_menu.selection];
+
+    if row == SettingsRow::Controls {
+        if menu_input.confirm {
+            next_menu_screen.set(MenuScreen::Controls);
+        }
+    } else {
+        let step = if menu_input.left {
+            -1
+        } else if menu_input.right || menu_input.confirm {
+            1
+        } else {
+            0
+        };
+
+        // only touch Settings when something actually changes, anything
+        // watching it for changes re-applies the whole thing
+        if step != 0 {
+            change_setting(&mut settings, row, step);
+        }
+    }
+
+    if menu_input.back {
+        save_requested_event_writer.send(SaveRequested {});
+        next_menu_screen.set(settings_menu.return_to);
+    }
+}
+
+pub fn update_settings_entries(
+    settings: Res<Settings>,
+    settings_menu: Res<SettingsMenu>,
+    mut entry_query: Query<(&mut Text, &SettingsEntry)>
+) {
+    for (mut text, entry) in entry_query.iter_mut() {
+        text.sections[0].value = setting_text(&settings, SETTINGS_ROWS[entry.index]);
+        text.sections[0].style.color = if entry.index == settings_menu.selection {
+            SELECTED_COLOR
+        } else {
+            UNSELECTED_COLOR
+        };
+    }
+}
+
+// steps a setting forwards (1) or backwards (-1), wrapping lists and clamping sliders
+fn change_setting(settings: &mut Settings, row: SettingsRow, step: i32) {
+    match row {
+        SettingsRow::WindowMode => {
+            let modes = [
+                WindowModeSetting::Windowed,
+                WindowModeSetting::Borderless,
+                WindowModeSetting::Fullscreen,
+            ];
+            let current = modes
+                .iter()
+                .position(|mode| *mode == settings.window_mode)
+                .unwrap_or(0);
+            settings.window_mode = modes[wrap_index(current, step, modes.len())];
+        }
+        SettingsRow::Resolution => {
+            let current = RESOLUTIONS.iter()
+                .position(|resolution| *resolution == settings.resolution)
+                .unwrap_or(0);
+            settings.resolution = RESOLUTIONS[wrap_index(current, step, RESOLUTIONS.len())];
+        }
+        SettingsRow::Vsync => {
+            settings.vsync = !settings.vsync;
+        }
+        SettingsRow::MasterVolume => {
+            settings.master_volume = step_slider(settings.master_volume, step);
+        }
+        SettingsRow::MusicVolume => {
+            settings.music_volume = step_slider(settings.music_volume, step);
+        }
+        SettingsRow::SfxVolume => {
+            settings.sfx_volume = step_slider(settings.sfx_volume, step);
+        }
+        SettingsRow::ScreenShake => {
+            settings.screen_shake = step_slider(settings.screen_shake, step);
+        }
+        SettingsRow::DebugRender => {
+            settings.show_debug_render = !settings.show_debug_render;
+        }
+        SettingsRow::Ghost => {
+            settings.show_ghost = !settings.show_ghost;
+        }
+        SettingsRow::SpeedrunTimer => {
+            settings.speedrun_timer = !settings.speedrun_timer;
+        }
+        SettingsRow::AssistHealth => {
+            settings.assist_health = ((settings.assist_health as i32) + step).clamp(
+                0,
+                MAX_ASSIST_HEALTH as i32
+            ) as u32;
+        }
+        SettingsRow::StickDeadzone => {
+            // 5% steps, and never so high the stick can't get past it
+            settings.stick_deadzone = (
+                ((settings.stick_deadzone * 20.0).round() + (step as f32)) /
+                20.0
+            ).clamp(0.05, 0.9);
+        }
+        // opens its own screen instead
+        SettingsRow::Controls => {}
+    }
+}
+
+fn setting_text(settings: &Settings, row: SettingsRow) -> String {
+    let on_off = |value: bool| if value { "on" } else { "off" };
+
+    match row {
+        SettingsRow::WindowMode => {
+            let mode = match settings.window_mode {
+                WindowModeSetting::Windowed => "windowed",
+                WindowModeSetting::Borderless => "borderless",
+                WindowModeSetting::Fullscreen => "fullscreen",
+            };
+            format!("Window mode    < {} >", mode)
+        }
+        SettingsRow::Resolution =>
+            format!("Resolution    < {}x{} >", settings.resolution.0, settings.resolution.1),
+        SettingsRow::Vsync => format!("Vsync    < {} >", on_off(settings.vsync)),
+        SettingsRow::MasterVolume =>
+            format!("Master volume    < {:.0}% >", settings.master_volume * 100.0),
+        SettingsRow::MusicVolume =>
+            format!("Music volume    < {:.0}% >", settings.music_volume * 100.0),
+        SettingsRow::SfxVolume => format!("SFX volume    < {:.0}% >", settings.sfx_volume * 100.0),
+        SettingsRow::ScreenShake =>
+            format!("Screen shake    < {:.0}% >", settings.screen_shake * 100.0),
+        SettingsRow::DebugRender =>
+            format!("Debug hitboxes    < {} >", on_off(settings.show_debug_render)),
+        SettingsRow::Ghost => format!("Best run ghost    < {} >", on_off(settings.show_ghost)),
+        SettingsRow::SpeedrunTimer =>
+            format!("Speedrun timer    < {} >", on_off(settings.speedrun_timer)),
+        SettingsRow::AssistHealth =>
+            match settings.assist_health {
+                0 => "Assist health    < off >".to_string(),
+                hits => format!("Assist health    < {} hits >", hits),
+            }
+        SettingsRow::StickDeadzone =>
+            format!("Stick deadzone    < {:.0}% >", settings.stick_deadzone * 100.0),
+        SettingsRow::Controls => "Controls...".to_string(),
+    }
+}
+
+fn wrap_index(index: usize, step: i32, len: usize) -> usize {
+    ((index as i32) + step).rem_euclid(len as i32) as usize
+}
+
+// sliders go from 0% to 100% in steps of 10%
+fn step_slider(value: f32, step: i32) -> f32 {
+    (((value * 10.0).round() + (step as f32)) / 10.0).clamp(0.0, 1.0)
+}
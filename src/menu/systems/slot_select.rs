@@ -0,0 +1,286 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+
+use crate::controls::resources::{ ActionState, InputDevice };
+use crate::ghost::resources::Ghosts;
+use crate::level::resources::CampaignProgress;
+use crate::menu::components::*;
+use crate::menu::resources::*;
+use crate::menu::systems::MenuInput;
+use crate::menu::{ LOCKED_COLOR, MENU_BACKGROUND_COLOR, SELECTED_COLOR, UNSELECTED_COLOR };
+use crate::save::file::{ copy_slot, delete_slot, open_slot };
+use crate::save::resources::{ ActiveSlot, SaveRequested };
+use crate::save::SLOT_COUNT;
+use crate::MenuScreen;
+
+// -- SLOT SELECT --
+
+pub fn spawn_slot_select(mut commands: Commands, mut slot_menu: ResMut<SlotMenu>) {
+    slot_menu.mode = SlotMenuMode::Browse;
+    slot_menu.refresh();
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.0),
+                    ..default()
+                },
+                background_color: MENU_BACKGROUND_COLOR.into(),
+                ..default()
+            },
+            SlotSelectMenu {},
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section("Select Save", TextStyle {
+                    font_size: 48.0,
+                    ..default()
+                })
+            );
+
+            // text gets filled in by update_slot_select_entries
+            for index in 0..SLOT_COUNT {
+                parent.spawn((
+                    TextBundle::from_section("", TextStyle { font_size: 28.0, ..default() }),
+                    SlotSelectEntry { index },
+                ));
+            }
+
+            parent.spawn((
+                TextBundle::from_section("", TextStyle { font_size: 20.0, ..default() }),
+                SlotSelectPrompt {},
+            ));
+        });
+}
+
+pub fn despawn_slot_select(
+    mut commands: Commands,
+    menu_query: Query<Entity, With<SlotSelectMenu>>
+) {
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn slot_select_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    action_state: Res<ActionState>,
+    mut slot_menu: ResMut<SlotMenu>,
+    mut active_slot: ResMut<ActiveSlot>,
+    mut progress: ResMut<CampaignProgress>,
+    mut ghosts: ResMut<Ghosts>,
+    mut level_selection: ResMut<LevelSelection>,
+    mut settings_menu: ResMut<SettingsMenu>,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
+    mut save_requested_event_writer: EventWriter<SaveRequested>,
+    mut app_exit_event_writer: EventWriter<AppExit>
+) {
+    let menu_input = MenuInput::read(&action_state, &keyboard_input, &gamepad_buttons);
+    let select = menu_input.confirm;
+    let back = menu_input.back;
+    let yes = keyboard_input.just_pressed(KeyCode::KeyY) || select;
+    let no = keyboard_input.just_pressed(KeyCode::KeyN) || back;
+
+    match slot_menu.mode {
+        SlotMenuMode::Browse | SlotMenuMode::PickCopyTarget { .. } => {
+            slot_menu.selection = menu_input.scroll(slot_menu.selection, SLOT_COUNT);
+        }
+        _ => {}
+    }
+
+    let selection = slot_menu.selection;
+    let selected_is_empty = matches!(slot_menu.slots[selection], SlotSummary::Empty);
+
+    match slot_menu.mode {
+        SlotMenuMode::Browse => {
+            if select {
+                // load whatever's in the slot, an empty slot is a new game
+                match open_slot(selection) {
+                    Ok(Some(slot)) => {
+                        *progress = slot.progress;
+                        *ghosts = slot.ghosts;
+                        active_slot.playtime = slot.playtime;
+                    }
+                    Ok(None) => {
+                        *progress = CampaignProgress::default();
+                        *ghosts = Ghosts::default();
+                        active_slot.playtime = 0.0;
+                    }
+                    // only a newer build's slot comes back as an error, and playing
+                    // in it would write over it
+                    Err(error) => {
+                        error!("can't open save slot {}: {}", selection + 1, error);
+                        slot_menu.mode = SlotMenuMode::CantOpen { index: selection };
+                        return;
+                    }
+                }
+                active_slot.index = Some(selection);
+                level_selection.index = 0;
+                // writes the slot right away so it shows up as last played
+                save_requested_event_writer.send(SaveRequested {});
+                next_menu_screen.set(MenuScreen::LevelSelect);
+            } else if
+                (keyboard_input.just_pressed(KeyCode::KeyC) || menu_input.secondary) &&
+                !selected_is_empty
+            {
+                slot_menu.mode = SlotMenuMode::PickCopyTarget { source: selection };
+            } else if
+                (keyboard_input.just_pressed(KeyCode::KeyX) ||
+                    keyboard_input.just_pressed(KeyCode::Delete) ||
+                    menu_input.tertiary) &&
+                !selected_is_empty
+            {
+                slot_menu.mode = SlotMenuMode::ConfirmDelete { index: selection };
+            } else if keyboard_input.just_pressed(KeyCode::KeyO) || menu_input.options {
+                settings_menu.return_to = MenuScreen::SlotSelect;
+                next_menu_screen.set(MenuScreen::Settings);
+            } else if back {
+                app_exit_event_writer.send(AppExit);
+            }
+        }
+        SlotMenuMode::PickCopyTarget { source } => {
+            if select && selection != source {
+                slot_menu.mode = SlotMenuMode::ConfirmCopy { source, target: selection };
+            } else if back {
+                slot_menu.mode = SlotMenuMode::Browse;
+            }
+        }
+        SlotMenuMode::ConfirmCopy { source, target } => {
+            if yes {
+                if let Err(error) = copy_slot(source, target) {
+                    error!("couldn't copy save slot {}: {}", source + 1, error);
+                }
+                if active_slot.index == Some(target) {
+                    forget_active_slot(&mut active_slot, &mut progress, &mut ghosts);
+                }
+                slot_menu.refresh();
+                slot_menu.mode = SlotMenuMode::Browse;
+            } else if no {
+                slot_menu.mode = SlotMenuMode::Browse;
+            }
+        }
+        SlotMenuMode::ConfirmDelete { index } => {
+            if yes {
+                if let Err(error) = delete_slot(index) {
+                    error!("couldn't delete save slot {}: {}", index + 1, error);
+                }
+                if active_slot.index == Some(index) {
+                    forget_active_slot(&mut active_slot, &mut progress, &mut ghosts);
+                }
+                slot_menu.refresh();
+                slot_menu.mode = SlotMenuMode::Browse;
+            } else if no {
+                slot_menu.mode = SlotMenuMode::Browse;
+            }
+        }
+        SlotMenuMode::CantOpen { .. } => {
+            if select || back {
+                slot_menu.mode = SlotMenuMode::Browse;
+            }
+        }
+    }
+}
+
+// the slot that was being played just got deleted or copied over, so what's still in
+// memory mustn't get written back into it by the next save
+fn forget_active_slot(
+    active_slot: &mut ActiveSlot,
+    progress: &mut CampaignProgress,
+    ghosts: &mut Ghosts
+) {
+    *active_slot = ActiveSlot::default();
+    *progress = CampaignProgress::default();
+    *ghosts = Ghosts::default();
+}
+
+pub fn update_slot_select_entries(
+    slot_menu: Res<SlotMenu>,
+    input_device: Res<InputDevice>,
+    mut entry_query: Query<(&mut Text, &SlotSelectEntry)>,
+    mut prompt_query: Query<&mut Text, (With<SlotSelectPrompt>, Without<SlotSelectEntry>)>
+) {
+    if !slot_menu.is_changed() && !input_device.is_changed() {
+        return;
+    }
+
+    for (mut text, entry) in entry_query.iter_mut() {
+        text.sections[0].value = match slot_menu.slots[entry.index] {
+            SlotSummary::Empty => format!("Slot {}    empty", entry.index + 1),
+            SlotSummary::Unreadable => format!("Slot {}    unreadable", entry.index + 1),
+            SlotSummary::Newer => format!("Slot {}    from a newer version", entry.index + 1),
+            SlotSummary::Saved { playtime, completion, last_played } =>
+                format!(
+                    "Slot {}    {:.0}%    {} played    last played {}",
+                    entry.index + 1,
+                    completion,
+                    format_playtime(playtime),
+                    format_date(last_played)
+                ),
+        };
+        text.sections[0].style.color = match slot_menu.mode {
+            SlotMenuMode::PickCopyTarget { source } if entry.index == source => LOCKED_COLOR,
+            _ if entry.index == slot_menu.selection => SELECTED_COLOR,
+            _ => UNSELECTED_COLOR,
+        };
+    }
+
+    if let Ok(mut text) = prompt_query.get_single_mut() {
+        let yes_no = input_device.prompt("Y / N", "A / B");
+        text.sections[0].value = match slot_menu.mode {
+            SlotMenuMode::Browse =>
+                input_device
+                    .prompt(
+                        "Enter to play, C to copy, X to delete, O for settings, Esc to quit",
+                        "A to play, X to copy, Y to delete, Select for settings, B to quit"
+                    )
+                    .to_string(),
+            SlotMenuMode::PickCopyTarget { source } =>
+                format!(
+                    "Copy slot {} to which slot? {}",
+                    source + 1,
+                    input_device.prompt("Enter to pick, Esc to cancel", "A to pick, B to cancel")
+                ),
+            SlotMenuMode::ConfirmCopy { source, target } =>
+                format!("Copy slot {} over slot {}? {}", source + 1, target + 1, yes_no),
+            SlotMenuMode::ConfirmDelete { index } =>
+                format!("Delete slot {}? This can't be undone. {}", index + 1, yes_no),
+            SlotMenuMode::CantOpen { index } =>
+                format!(
+                    "Slot {} needs a newer version of the game. {}",
+                    index + 1,
+                    input_device.prompt("Enter to go back", "A to go back")
+                ),
+        };
+    }
+}
+
+// -- FORMATTING --
+
+// formats seconds as h:mm:ss
+fn format_playtime(seconds: f32) -> String {
+    let seconds = seconds as u64;
+    format!("{}:{:02}:{:02}", seconds / 3600, (seconds / 60) % 60, seconds % 60)
+}
+
+// formats a unix timestamp as yyyy-mm-dd (utc), using Howard Hinnant's civil_from_days
+fn format_date(unix_seconds: u64) -> String {
+    let days = ((unix_seconds / 86400) as i64) + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + (if month <= 2 { 1 } else { 0 });
+
+    format!("{}-{:02}-{:02}", year, month, day)
+}
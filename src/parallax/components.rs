@@ -0,0 +1,11 @@
+use bevy::prelude::*;
+
+// -- COMPONENTS --
+
+#[derive(Component)]
+pub struct ParallaxLayer {
+    pub scroll: Vec2,
+    pub offset_y: f32,
+    // width of one tile for layers that repeat sideways
+    pub tile_width: Option<f32>,
+}
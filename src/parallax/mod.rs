@@ -0,0 +1,37 @@
+// import bevy crates
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+// import modules
+pub mod components;
+pub mod resources;
+pub mod systems;
+
+// use modules
+use resources::*;
+use systems::*;
+use crate::level::systems::spawn_current_level;
+use crate::AppState;
+
+// depth of the furthest layer, each one after it is a little closer
+pub const PARALLAX_Z: f32 = -100.0;
+// how much wider than the window repeating layers are laid out, so zoomed out
+// rooms don't run off the end of them
+pub const PARALLAX_COVERAGE: f32 = 1.5;
+
+pub struct ParallaxPlugin;
+
+impl Plugin for ParallaxPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ParallaxLayers>()
+            .add_systems(
+                OnEnter(AppState::Game),
+                spawn_parallax_layers.after(spawn_current_level)
+            )
+            // after the camera's done moving for the frame, shake included
+            .add_systems(
+                PostUpdate,
+                scroll_parallax.before(TransformSystem::TransformPropagate)
+            );
+    }
+}
@@ -0,0 +1,30 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+// -- RESOURCES --
+
+// the current level's background layers, furthest away first
+#[derive(Resource, Default)]
+pub struct ParallaxLayers {
+    pub layers: Vec<ParallaxLayerData>,
+}
+
+// -- LEVEL DATA --
+
+// one background image in a level file
+#[derive(Deserialize, Clone)]
+pub struct ParallaxLayerData {
+    // path under assets/
+    pub image: String,
+    // full width/height it's drawn at, one tile's worth if it repeats
+    pub size: (f32, f32),
+    // how far it moves compared to the level. 0.0 sits still on screen like the sky
+    // and 1.0 scrolls along with the platforms
+    pub scroll: (f32, f32),
+    // tiles it sideways forever instead of drawing it once
+    #[serde(default)]
+    pub repeat: bool,
+    // where it sits vertically when the camera's at y = 0
+    #[serde(default)]
+    pub offset_y: f32,
+}
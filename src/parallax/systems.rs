@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::camera::components::CameraController;
+use crate::level::components::LevelEntity;
+use crate::parallax::components::*;
+use crate::parallax::resources::*;
+use crate::parallax::{ PARALLAX_COVERAGE, PARALLAX_Z };
+
+pub fn spawn_parallax_layers(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    parallax_layers: Res<ParallaxLayers>,
+    window_query: Query<&Window, With<PrimaryWindow>>
+) {
+    let window: &Window = window_query.get_single().unwrap();
+
+    for (index, layer) in parallax_layers.layers.iter().enumerate() {
+        let texture = asset_server.load(layer.image.clone());
+        let sprite = Sprite {
+            custom_size: Some(Vec2::new(layer.size.0, layer.size.1)),
+            ..default()
+        };
+
+        // enough tiles either side of the middle one to cover the screen however far
+        // the layer has drifted within a tile
+        let tiles = if layer.repeat {
+            let half_view = (window.width() * PARALLAX_COVERAGE) / 2.0;
+            (half_view / layer.size.0).ceil() as i32 + 1
+        } else {
+            0
+        };
+
+        commands
+            .spawn((
+                SpatialBundle::from_transform(
+                    Transform::from_xyz(0.0, layer.offset_y, PARALLAX_Z + (index as f32))
+                ),
+                ParallaxLayer {
+                    scroll: Vec2::new(layer.scroll.0, layer.scroll.1),
+                    offset_y: layer.offset_y,
+                    tile_width: layer.repeat.then_some(layer.size.0),
+                },
+                LevelEntity {},
+            ))
+            .with_children(|parent| {
+                for tile in -tiles..=tiles {
+                    parent.spawn(SpriteBundle {
+                        sprite: sprite.clone(),
+                        texture: texture.clone(),
+                        transform: Transform::from_xyz((tile as f32) * layer.size.0, 0.0, 0.0),
+                        ..default()
+                    });
+                }
+            });
+    }
+}
+
+// layers follow the camera by 1.0 - scroll, so 0.0 keeps right up with it and 1.0
+// doesn't move at all
+pub fn scroll_parallax(
+    camera_query: Query<&Transform, With<CameraController>>,
+    mut layer_query: Query<(&mut Transform, &ParallaxLayer), Without<CameraController>>
+) {
+    let Ok(camera_transform) = camera_query.get_single() else {
+        return;
+    };
+    let camera = camera_transform.translation.truncate();
+
+    for (mut transform, layer) in layer_query.iter_mut() {
+        let mut position = camera * (Vec2::ONE - layer.scroll);
+        position.y += layer.offset_y;
+
+        // jump a whole tile at a time to stay under the camera, which looks the same
+        if let Some(tile_width) = layer.tile_width {
+            position.x += ((camera.x - position.x) / tile_width).round() * tile_width;
+        }
+
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
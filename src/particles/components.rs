@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+
+// -- COMPONENTS --
+
+// one of the pooled sprites, hidden and sitting in ParticlePool while it isn't in use
+#[derive(Component, Default)]
+pub struct Particle {
+    pub active: bool,
+    // index into ParticleEffects
+    pub effect: usize,
+    pub velocity: Vec2,
+    pub age: f32,
+    pub lifetime: f32,
+    // what the size curve is multiplied by, the copied sprite's size for afterimages
+    pub base_size: Vec2,
+    // showing a copy of another sprite rather than a plain square
+    pub afterimage: bool,
+}
+
+// keeps sending out an effect from wherever the entity is while it's on
+#[derive(Component)]
+pub struct ParticleEmitter {
+    pub effect: &'static str,
+    pub active: bool,
+    // particles owed since the last one went out
+    pub owed: f32,
+}
+
+impl ParticleEmitter {
+    pub fn new(effect: &'static str) -> ParticleEmitter {
+        ParticleEmitter {
+            effect,
+            active: false,
+            owed: 0.0,
+        }
+    }
+}
@@ -0,0 +1,51 @@
+// import bevy crates
+use bevy::prelude::*;
+
+// import modules
+pub mod components;
+pub mod resources;
+pub mod systems;
+
+// use modules
+use resources::*;
+use systems::*;
+use crate::animation::systems::animate_player;
+use crate::data::load_ron;
+use crate::{ AppState, SimulationState };
+
+pub const PARTICLE_EFFECTS_FILE: &str = "particles/effects.ron";
+// every particle there can be at once, spawned up front and reused. when they're
+// all in use new ones just don't show up
+pub const PARTICLE_POOL_SIZE: usize = 512;
+// just behind the player, in front of the backgrounds
+pub const PARTICLE_Z: f32 = -1.0;
+// landings slower than this don't kick up a puff, in pixels a second
+pub const LANDING_PUFF_SPEED: f32 = 400.0;
+
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        let effects = ParticleEffects::new(load_ron(PARTICLE_EFFECTS_FILE).unwrap());
+
+        app.insert_resource(effects)
+            .init_resource::<ParticlePool>()
+            .init_resource::<ParticleRng>()
+            .add_event::<SpawnParticles>()
+            .add_systems(Startup, spawn_particle_pool)
+            // cosmetic only, so these follow the frame rate and roll their own numbers
+            .add_systems(
+                Update,
+                (
+                    update_player_emitters,
+                    run_emitters,
+                    dust_on_footsteps.after(animate_player),
+                    spawn_particles,
+                    update_particles,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Game).and_then(in_state(SimulationState::Running)))
+            )
+            .add_systems(OnExit(AppState::Game), clear_particles);
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::rng::GameRng;
+
+// -- RESOURCES --
+
+#[derive(Resource)]
+pub struct ParticleEffects {
+    pub effects: Vec<ParticleEffectData>,
+    ids: HashMap<String, usize>,
+}
+
+impl ParticleEffects {
+    pub fn new(effects: HashMap<String, ParticleEffectData>) -> ParticleEffects {
+        let mut ids = HashMap::new();
+        let effects = effects
+            .into_iter()
+            .enumerate()
+            .map(|(index, (name, effect))| {
+                ids.insert(name, index);
+                effect
+            })
+            .collect();
+
+        ParticleEffects { effects, ids }
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+}
+
+// the hidden particles ready to be used
+#[derive(Resource, Default)]
+pub struct ParticlePool {
+    pub free: Vec<Entity>,
+}
+
+// particles get their own rng so they never take numbers out of GameRng and throw
+// off replays
+#[derive(Resource)]
+pub struct ParticleRng {
+    pub rng: GameRng,
+}
+
+impl Default for ParticleRng {
+    fn default() -> ParticleRng {
+        ParticleRng { rng: GameRng::new(0) }
+    }
+}
+
+// -- EVENTS --
+
+// fires an effect once at a position. anything with an afterimage effect copies the
+// look of the source sprite
+#[derive(Event)]
+pub struct SpawnParticles {
+    pub effect: &'static str,
+    pub position: Vec2,
+    pub source: Option<Entity>,
+}
+
+// -- PARTICLE DATA --
+
+#[derive(Deserialize)]
+pub struct ParticleEffectData {
+    // how many come out each time it's fired
+    #[serde(default = "default_count")]
+    pub count: u32,
+    // how many a second an emitter sends out
+    #[serde(default)]
+    pub rate: f32,
+    // seconds, each particle picks somewhere between the two
+    pub lifetime: (f32, f32),
+    // pixels a second
+    pub speed: (f32, f32),
+    // degrees, 0.0 is right and 90.0 straight up
+    #[serde(default)]
+    pub direction: f32,
+    // degrees either side of direction
+    #[serde(default)]
+    pub spread: f32,
+    // pixels a second squared, pulling down
+    #[serde(default)]
+    pub gravity: f32,
+    // how far from the position they can start, each way
+    #[serde(default)]
+    pub jitter: (f32, f32),
+    // (how far through its life, rgba) keys, blended in between
+    pub color: Vec<(f32, (f32, f32, f32, f32))>,
+    // (how far through its life, size) keys, in pixels or times the copied sprite's size
+    pub size: Vec<(f32, f32)>,
+    #[serde(default)]
+    pub afterimage: bool,
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+impl ParticleEffectData {
+    pub fn color_at(&self, t: f32) -> Color {
+        let (r, g, b, a) = sample(&self.color, t, (1.0, 1.0, 1.0, 1.0), |from, to, blend| {
+            (
+                from.0 + (to.0 - from.0) * blend,
+                from.1 + (to.1 - from.1) * blend,
+                from.2 + (to.2 - from.2) * blend,
+                from.3 + (to.3 - from.3) * blend,
+            )
+        });
+        Color::rgba(r, g, b, a)
+    }
+
+    pub fn size_at(&self, t: f32) -> f32 {
+        sample(&self.size, t, 1.0, |from, to, blend| from + (to - from) * blend)
+    }
+}
+
+// blends between whichever two keys t falls between, holding the ends past them
+fn sample<T: Copy>(keys: &[(f32, T)], t: f32, empty: T, lerp: impl Fn(T, T, f32) -> T) -> T {
+    let Some(&(first_t, first)) = keys.first() else {
+        return empty;
+    };
+    if t <= first_t {
+        return first;
+    }
+
+    for pair in keys.windows(2) {
+        let ((from_t, from), (to_t, to)) = (pair[0], pair[1]);
+        if t <= to_t {
+            let blend = if to_t > from_t { (t - from_t) / (to_t - from_t) } else { 1.0 };
+            return lerp(from, to, blend);
+        }
+    }
+
+    keys[keys.len() - 1].1
+}
@@ -0,0 +1,240 @@
+use bevy::prelude::*;
+
+use crate::animation::resources::AnimationEvent;
+use crate::juice::resources::HitStopTimer;
+use crate::particles::components::*;
+use crate::particles::resources::*;
+use crate::particles::{ PARTICLE_POOL_SIZE, PARTICLE_Z };
+use crate::{ Dash, Player, HALF_PLAYER };
+
+// -- POOL --
+
+pub fn spawn_particle_pool(mut commands: Commands, mut pool: ResMut<ParticlePool>) {
+    for _ in 0..PARTICLE_POOL_SIZE {
+        let particle = commands
+            .spawn((
+                SpriteBundle {
+                    transform: Transform::from_xyz(0.0, 0.0, PARTICLE_Z),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                Particle::default(),
+            ))
+            .id();
+        pool.free.push(particle);
+    }
+}
+
+pub fn clear_particles(
+    mut commands: Commands,
+    mut pool: ResMut<ParticlePool>,
+    mut particle_query: Query<(Entity, &mut Particle, &mut Sprite)>,
+    mut look_query: Query<(&mut Visibility, &mut Handle<Image>), With<Particle>>
+) {
+    for (entity, mut particle, mut sprite) in particle_query.iter_mut() {
+        if particle.active {
+            if let Ok((mut visibility, mut image)) = look_query.get_mut(entity) {
+                release(&mut commands, &mut pool, entity, &mut particle, &mut sprite);
+                *visibility = Visibility::Hidden;
+                *image = default();
+            }
+        }
+    }
+}
+
+// hides it and puts it back for the next effect to use
+fn release(
+    commands: &mut Commands,
+    pool: &mut ParticlePool,
+    entity: Entity,
+    particle: &mut Particle,
+    sprite: &mut Sprite
+) {
+    if particle.afterimage {
+        commands.entity(entity).remove::<TextureAtlas>();
+        sprite.flip_x = false;
+    }
+    particle.active = false;
+    pool.free.push(entity);
+}
+
+// -- EMITTING --
+
+// the player leaves afterimages behind while dashing
+pub fn update_player_emitters(mut player_query: Query<(&Dash, &mut ParticleEmitter), With<Player>>) {
+    for (dash, mut emitter) in player_query.iter_mut() {
+        emitter.active = dash.is_dashing;
+    }
+}
+
+pub fn run_emitters(
+    time: Res<Time>,
+    effects: Res<ParticleEffects>,
+    mut emitter_query: Query<(&GlobalTransform, &mut ParticleEmitter, Option<&Children>)>,
+    sprite_query: Query<&GlobalTransform, (With<Sprite>, Without<Particle>)>,
+    mut spawn_particles_event_writer: EventWriter<SpawnParticles>
+) {
+    for (transform, mut emitter, children) in emitter_query.iter_mut() {
+        let Some(effect) = effects.id(emitter.effect).map(|id| &effects.effects[id]) else {
+            continue;
+        };
+        if !emitter.active {
+            emitter.owed = 0.0;
+            continue;
+        }
+
+        // afterimages copy whatever sprite the entity's drawn with, and go where it's drawn
+        let source = if effect.afterimage {
+            children.and_then(|children| {
+                children.iter().find(|child| sprite_query.contains(**child)).copied()
+            })
+        } else {
+            None
+        };
+        let position = source
+            .and_then(|source| sprite_query.get(source).ok())
+            .unwrap_or(transform)
+            .translation()
+            .truncate();
+
+        emitter.owed += effect.rate * time.delta_seconds();
+        while emitter.owed >= 1.0 {
+            emitter.owed -= 1.0;
+            spawn_particles_event_writer.send(SpawnParticles {
+                effect: emitter.effect,
+                position,
+                source,
+            });
+        }
+    }
+}
+
+// a puff of dust at the player's feet on every footstep of the run animation
+pub fn dust_on_footsteps(
+    mut animation_event_reader: EventReader<AnimationEvent>,
+    transform_query: Query<&GlobalTransform>,
+    mut spawn_particles_event_writer: EventWriter<SpawnParticles>
+) {
+    for event in animation_event_reader.read() {
+        if event.name != "footstep" {
+            continue;
+        }
+
+        if let Ok(transform) = transform_query.get(event.entity) {
+            spawn_particles_event_writer.send(SpawnParticles {
+                effect: "run_dust",
+                position: transform.translation().truncate() - Vec2::new(0.0, HALF_PLAYER),
+                source: None,
+            });
+        }
+    }
+}
+
+pub fn spawn_particles(
+    mut commands: Commands,
+    effects: Res<ParticleEffects>,
+    mut pool: ResMut<ParticlePool>,
+    mut particle_rng: ResMut<ParticleRng>,
+    mut spawn_particles_event_reader: EventReader<SpawnParticles>,
+    mut particle_query: Query<(&mut Particle, &mut Transform, &mut Sprite)>,
+    mut look_query: Query<(&mut Visibility, &mut Handle<Image>), With<Particle>>,
+    source_query: Query<(&Handle<Image>, &Sprite), Without<Particle>>,
+    atlas_query: Query<&TextureAtlas, Without<Particle>>
+) {
+    let rng = &mut particle_rng.rng;
+    for event in spawn_particles_event_reader.read() {
+        let Some(id) = effects.id(event.effect) else {
+            warn!("no particle effect called {}", event.effect);
+            continue;
+        };
+        let effect = &effects.effects[id];
+        let source = event.source.filter(|_| effect.afterimage);
+        let look = source.and_then(|source| source_query.get(source).ok());
+        let atlas = source.and_then(|source| atlas_query.get(source).ok());
+
+        for _ in 0..effect.count {
+            let Some(entity) = pool.free.pop() else {
+                break;
+            };
+            let (
+                Ok((mut particle, mut transform, mut sprite)),
+                Ok((mut visibility, mut image)),
+            ) = (particle_query.get_mut(entity), look_query.get_mut(entity)) else {
+                continue;
+            };
+
+            let angle = (effect.direction + rng.range((-effect.spread, effect.spread))).to_radians();
+            let jitter = Vec2::new(
+                rng.range((-effect.jitter.0, effect.jitter.0)),
+                rng.range((-effect.jitter.1, effect.jitter.1))
+            );
+
+            *particle = Particle {
+                active: true,
+                effect: id,
+                velocity: Vec2::from_angle(angle) * rng.range(effect.speed),
+                age: 0.0,
+                lifetime: rng.range(effect.lifetime).max(0.01),
+                base_size: Vec2::ONE,
+                afterimage: false,
+            };
+            if let Some((texture, source_sprite)) = look {
+                particle.afterimage = true;
+                particle.base_size = source_sprite.custom_size.unwrap_or(Vec2::ONE);
+                *image = texture.clone();
+                sprite.flip_x = source_sprite.flip_x;
+                if let Some(atlas) = atlas {
+                    commands.entity(entity).insert(atlas.clone());
+                }
+            } else {
+                *image = default();
+            }
+
+            transform.translation = (event.position + jitter).extend(PARTICLE_Z);
+            sprite.color = effect.color_at(0.0);
+            sprite.custom_size = Some(particle.base_size * effect.size_at(0.0));
+            *visibility = Visibility::Inherited;
+        }
+    }
+}
+
+// -- UPDATING --
+
+pub fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    hit_stop_timer: Res<HitStopTimer>,
+    effects: Res<ParticleEffects>,
+    mut pool: ResMut<ParticlePool>,
+    mut particle_query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+    mut visibility_query: Query<&mut Visibility, With<Particle>>
+) {
+    // they hang in the air through a hit-stop along with everything else
+    if hit_stop_timer.frozen {
+        return;
+    }
+    let dt = time.delta_seconds();
+
+    for (entity, mut particle, mut transform, mut sprite) in particle_query.iter_mut() {
+        if !particle.active {
+            continue;
+        }
+
+        particle.age += dt;
+        if particle.age >= particle.lifetime {
+            release(&mut commands, &mut pool, entity, &mut particle, &mut sprite);
+            if let Ok(mut visibility) = visibility_query.get_mut(entity) {
+                *visibility = Visibility::Hidden;
+            }
+            continue;
+        }
+
+        let effect = &effects.effects[particle.effect];
+        particle.velocity.y -= effect.gravity * dt;
+        transform.translation += (particle.velocity * dt).extend(0.0);
+
+        let t = particle.age / particle.lifetime;
+        sprite.color = effect.color_at(t);
+        sprite.custom_size = Some(particle.base_size * effect.size_at(t));
+    }
+}
@@ -0,0 +1,65 @@
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use bevy::prelude::*;
+
+use crate::data::{ save_dir, write_save_file };
+use crate::replay::resources::*;
+use crate::replay::{ MAX_REPLAYS, REPLAY_FOLDER, REPLAY_VERSION };
+use crate::save::systems::unix_now;
+
+// -- REPLAY FILES --
+
+pub fn replay_path_from_args() -> Option<PathBuf> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--replay");
+    args.next()?;
+    args.next().map(PathBuf::from)
+}
+
+// replays get passed around in bug reports, so they're read from wherever they are
+pub fn load_replay(path: &Path) -> Result<Replay, String> {
+    let contents = fs::read_to_string(path).map_err(|error| error.to_string())?;
+    let header: ReplayHeader = ron::from_str(&contents).map_err(|error| error.to_string())?;
+
+    match header.version {
+        REPLAY_VERSION => ron::from_str(&contents).map_err(|error| error.to_string()),
+        version =>
+            Err(format!("replay version {} can't be played by version {}", version, REPLAY_VERSION)),
+    }
+}
+
+// written compact rather than pretty, they're long and nobody reads them by hand.
+// returns where it ended up so it can be logged for bug reports
+pub fn write_replay(replay: &Replay) -> Result<PathBuf, String> {
+    let contents = ron::to_string(replay).map_err(|error| error.to_string())?;
+    let file_name = format!("{}/{}-{}.ron", REPLAY_FOLDER, replay.level, unix_now());
+
+    write_save_file(&file_name, &contents).map_err(|error| error.to_string())?;
+    prune_replays();
+
+    Ok(save_dir().join(file_name))
+}
+
+// keeps the newest MAX_REPLAYS, every level attempt writes one
+fn prune_replays() {
+    let Ok(entries) = fs::read_dir(save_dir().join(REPLAY_FOLDER)) else {
+        return;
+    };
+
+    let mut replays: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|extension| extension == "ron"))
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((modified, entry.path()))
+        })
+        .collect();
+
+    replays.sort();
+    let excess = replays.len().saturating_sub(MAX_REPLAYS);
+    for (_, path) in replays.into_iter().take(excess) {
+        if let Err(error) = fs::remove_file(&path) {
+            error!("couldn't delete old replay {}: {}", path.display(), error);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+// import bevy crates
+use bevy::prelude::*;
+
+// import modules
+pub mod file;
+pub mod resources;
+pub mod systems;
+
+// use modules
+use file::{ load_replay, replay_path_from_args };
+use resources::*;
+use systems::*;
+use crate::level::resources::{ Campaign, CurrentLevel };
+use crate::rng::GameRng;
+use crate::save::systems::unix_now;
+use crate::{ player_movement, simulation_running, AppState, TickRate };
+
+// bump this whenever Replay or the packed input layout changes
+pub const REPLAY_VERSION: u32 = 3;
+pub const REPLAY_FOLDER: &str = "replays";
+// older replays get deleted once there are more than this many
+pub const MAX_REPLAYS: usize = 20;
+// how often the player's position is written down to check playback against, in ticks
+pub const CHECKSUM_INTERVAL: u32 = 30;
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameRng::new(unix_now()))
+            .init_resource::<ReplayRecording>()
+            .add_systems(OnEnter(AppState::Game), start_recording)
+            .add_systems(OnExit(AppState::Game), (finish_recording, finish_playback))
+            .add_systems(
+                FixedUpdate,
+                (play_back_frame, record_frame.after(play_back_frame))
+                    .before(player_movement)
+                    .run_if(simulation_running)
+            );
+
+        // `--replay <file>` skips the menus and goes straight into the recorded level.
+        // needs the campaign, so this plugin has to come after LevelPlugin
+        if let Some(path) = replay_path_from_args() {
+            let replay = match load_replay(&path) {
+                Ok(replay) => replay,
+                Err(error) => {
+                    error!("couldn't load replay {}: {}", path.display(), error);
+                    return;
+                }
+            };
+
+            let campaign = app.world.resource::<Campaign>();
+            let Some(index) = campaign.levels
+                .iter()
+                .position(|entry| entry.id == replay.level) else {
+                error!(
+                    "replay {} is for level {}, which isn't in the campaign",
+                    path.display(),
+                    replay.level
+                );
+                return;
+            };
+
+            info!(
+                "playing back {} on {} at {} ticks a second",
+                path.display(),
+                replay.level,
+                replay.tick_rate
+            );
+            app.world.resource_mut::<CurrentLevel>().index = index;
+            app.insert_resource(TickRate { hz: replay.tick_rate.max(1) })
+                .insert_resource(ReplayPlayback::new(replay))
+                .insert_state(AppState::Game);
+        }
+    }
+}
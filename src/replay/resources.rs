@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use serde::{ Deserialize, Serialize };
+
+// -- REPLAY DATA --
+
+// one attempt at a level, from loading in until it's left. a frame here is one
+// simulation tick, not a rendered frame
+#[derive(Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    // campaign id of the level it was recorded on
+    pub level: String,
+    // simulation ticks a second, playback runs at this rate whatever settings.ron says
+    pub tick_rate: u32,
+    // hits the player could take, 0 without assist mode. playback gives them the same
+    pub assist_health: u32,
+    // what GameRng was seeded with when the level started
+    pub seed: u64,
+    // runs of identical frames as (frame count, packed input), see pack_input
+    pub inputs: Vec<(u32, u32)>,
+    // (frame, checksum of the player's position at the start of that frame)
+    pub checksums: Vec<(u32, u64)>,
+}
+
+impl Replay {
+    pub fn push_input(&mut self, input: u32) {
+        match self.inputs.last_mut() {
+            Some((count, last_input)) if *last_input == input => {
+                *count += 1;
+            }
+            _ => self.inputs.push((1, input)),
+        }
+    }
+
+    pub fn frame_count(&self) -> u32 {
+        self.inputs
+            .iter()
+            .map(|(count, _)| count)
+            .sum()
+    }
+
+    // the runs written back out, one entry per frame
+    pub fn frames(&self) -> Vec<u32> {
+        self.inputs
+            .iter()
+            .flat_map(|(count, input)| std::iter::repeat_n(*input, *count as usize))
+            .collect()
+    }
+}
+
+// read first so we know which version the rest of the file is
+#[derive(Deserialize)]
+pub struct ReplayHeader {
+    pub version: u32,
+}
+
+// -- RESOURCES --
+
+// the attempt being recorded, None outside of levels and while a replay plays back
+#[derive(Resource, Default)]
+pub struct ReplayRecording {
+    pub replay: Option<Replay>,
+    pub frame: u32,
+}
+
+// only exists while a replay passed in with --replay is playing
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    pub frames: Vec<u32>,
+    pub frame: u32,
+    // the last checksum that matched, and the first one that didn't
+    pub last_match: Option<u32>,
+    pub diverged_at: Option<u32>,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> ReplayPlayback {
+        ReplayPlayback {
+            frames: replay.frames(),
+            replay,
+            frame: 0,
+            last_match: None,
+            diverged_at: None,
+        }
+    }
+
+    pub fn frame_count(&self) -> u32 {
+        self.frames.len() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay() -> Replay {
+        Replay {
+            version: 0,
+            level: "level_01".to_string(),
+            tick_rate: 60,
+            assist_health: 0,
+            seed: 0,
+            inputs: Vec::new(),
+            checksums: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn identical_frames_share_a_run() {
+        let mut replay = replay();
+        for input in [0, 0, 0, 5, 5, 0] {
+            replay.push_input(input);
+        }
+
+        assert_eq!(replay.inputs, vec![(3, 0), (2, 5), (1, 0)]);
+        assert_eq!(replay.frame_count(), 6);
+    }
+
+    #[test]
+    fn runs_expand_back_to_every_frame() {
+        let frames = vec![7, 7, 1, 2, 2, 2, 7];
+        let mut replay = replay();
+        for input in frames.iter() {
+            replay.push_input(*input);
+        }
+
+        assert_eq!(replay.frames(), frames);
+    }
+
+    #[test]
+    fn an_empty_replay_has_no_frames() {
+        let replay = replay();
+
+        assert_eq!(replay.frame_count(), 0);
+        assert!(replay.frames().is_empty());
+    }
+}
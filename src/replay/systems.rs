@@ -0,0 +1,243 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::controls::resources::{ Action, ActionState, TickActions, ACTIONS };
+use crate::controls::AIM_DIRECTIONS;
+use crate::data::hash_floats;
+use crate::level::resources::{ Campaign, CurrentLevel };
+use crate::replay::file::write_replay;
+use crate::replay::resources::*;
+use crate::replay::{ CHECKSUM_INTERVAL, REPLAY_VERSION };
+use crate::rng::GameRng;
+use crate::settings::resources::Settings;
+use crate::{ Player, TickRate };
+
+// -- RECORDING --
+
+pub fn start_recording(
+    campaign: Res<Campaign>,
+    current_level: Res<CurrentLevel>,
+    tick_rate: Res<TickRate>,
+    settings: Res<Settings>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut game_rng: ResMut<GameRng>,
+    mut recording: ResMut<ReplayRecording>
+) {
+    // a replay being played back brings its own seed and doesn't get recorded again
+    if let Some(playback) = playback {
+        *game_rng = GameRng::new(playback.replay.seed);
+        return;
+    }
+
+    let seed = game_rng.next_u64();
+    *game_rng = GameRng::new(seed);
+
+    recording.frame = 0;
+    recording.replay = Some(Replay {
+        version: REPLAY_VERSION,
+        level: campaign.levels[current_level.index].id.clone(),
+        tick_rate: tick_rate.hz,
+        assist_health: settings.assist_health,
+        seed,
+        inputs: Vec::new(),
+        checksums: Vec::new(),
+    });
+}
+
+pub fn record_frame(
+    tick_actions: Res<TickActions>,
+    player_query: Query<&Transform, With<Player>>,
+    mut recording: ResMut<ReplayRecording>
+) {
+    let frame = recording.frame;
+    let Some(replay) = recording.replay.as_mut() else {
+        return;
+    };
+
+    if frame.is_multiple_of(CHECKSUM_INTERVAL) {
+        if let Ok(transform) = player_query.get_single() {
+            replay.checksums.push((frame, position_checksum(transform.translation)));
+        }
+    }
+    replay.push_input(pack_input(&tick_actions.actions));
+
+    recording.frame += 1;
+}
+
+pub fn finish_recording(mut recording: ResMut<ReplayRecording>) {
+    if let Some(replay) = recording.replay.take() {
+        if replay.frame_count() == 0 {
+            return;
+        }
+
+        match write_replay(&replay) {
+            Ok(path) => info!("saved replay to {}", path.display()),
+            Err(error) => error!("couldn't save replay: {}", error),
+        }
+    }
+}
+
+// -- PLAYBACK --
+
+// swaps the live input for the recorded frame, and checks the player's still
+// where they were when it was recorded
+pub fn play_back_frame(
+    mut tick_actions: ResMut<TickActions>,
+    player_query: Query<&Transform, With<Player>>,
+    playback: Option<ResMut<ReplayPlayback>>
+) {
+    let Some(mut playback) = playback else {
+        return;
+    };
+    let frame = playback.frame;
+    let Some(&input) = playback.frames.get(frame as usize) else {
+        return;
+    };
+
+    let recorded_checksum = playback.replay.checksums
+        .binary_search_by_key(&frame, |(checksum_frame, _)| *checksum_frame)
+        .ok()
+        .map(|index| playback.replay.checksums[index].1);
+
+    let player = player_query.get_single();
+    if let (Some(recorded_checksum), Ok(transform)) = (recorded_checksum, player) {
+        if position_checksum(transform.translation) == recorded_checksum {
+            playback.last_match = Some(frame);
+        } else if playback.diverged_at.is_none() {
+            playback.diverged_at = Some(frame);
+            match playback.last_match {
+                Some(last_match) =>
+                    error!(
+                        "replay diverged by frame {}, last in the right place on frame {}",
+                        frame,
+                        last_match
+                    ),
+                None => error!("replay diverged by frame {}", frame),
+            }
+        }
+    }
+
+    unpack_input(input, &mut tick_actions.actions);
+    playback.frame += 1;
+
+    if playback.frame == playback.frame_count() {
+        match playback.diverged_at {
+            Some(frame) => info!("replay finished, it diverged by frame {}", frame),
+            None => info!("replay finished, all {} frames matched", playback.frame),
+        }
+    }
+}
+
+pub fn finish_playback(mut commands: Commands, playback: Option<Res<ReplayPlayback>>) {
+    if let Some(playback) = playback {
+        if playback.frame < playback.frame_count() {
+            info!("replay stopped on frame {} of {}", playback.frame, playback.frame_count());
+        }
+        // whatever comes after the recorded level is played live
+        commands.remove_resource::<ReplayPlayback>();
+    }
+}
+
+// -- PACKING --
+
+// pressed, just pressed and just released get a byte of action bits each, and the
+// top byte is the aim (0 for none, otherwise its index in AIM_DIRECTIONS plus one).
+// pause is left out, it works outside the simulation and stays live during playback
+fn pack_input(action_state: &ActionState) -> u32 {
+    let aim = AIM_DIRECTIONS.iter()
+        .position(|direction| *direction == action_state.aim)
+        .map_or(0, |index| (index as u32) + 1);
+
+    action_bits(&action_state.pressed) |
+        (action_bits(&action_state.just_pressed) << 8) |
+        (action_bits(&action_state.just_released) << 16) |
+        (aim << 24)
+}
+
+fn unpack_input(input: u32, action_state: &mut ActionState) {
+    set_action_bits(input, &mut action_state.pressed);
+    set_action_bits(input >> 8, &mut action_state.just_pressed);
+    set_action_bits(input >> 16, &mut action_state.just_released);
+    action_state.aim = match (input >> 24) as usize {
+        0 => Vec2::ZERO,
+        aim => AIM_DIRECTIONS[aim - 1],
+    };
+}
+
+fn action_bits(actions: &HashSet<Action>) -> u32 {
+    ACTIONS.iter()
+        .enumerate()
+        .filter(|(_, action)| **action != Action::Pause && actions.contains(action))
+        .fold(0, |bits, (index, _)| bits | (1 << index))
+}
+
+fn set_action_bits(bits: u32, actions: &mut HashSet<Action>) {
+    for (index, action) in ACTIONS.iter().enumerate() {
+        if *action == Action::Pause {
+            continue;
+        }
+
+        if bits & (1 << index) != 0 {
+            actions.insert(*action);
+        } else {
+            actions.remove(action);
+        }
+    }
+}
+
+fn position_checksum(translation: Vec3) -> u64 {
+    hash_floats(&[translation.x, translation.y])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action_state(pressed: &[Action], just_pressed: &[Action], aim: Vec2) -> ActionState {
+        ActionState {
+            pressed: pressed.iter().copied().collect(),
+            just_pressed: just_pressed.iter().copied().collect(),
+            aim,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn input_round_trips_through_packing() {
+        let recorded = ActionState {
+            just_released: [Action::Down].into_iter().collect(),
+            ..action_state(&[Action::MoveRight, Action::Jump], &[Action::Jump], AIM_DIRECTIONS[1])
+        };
+
+        let mut played = ActionState::default();
+        unpack_input(pack_input(&recorded), &mut played);
+
+        assert_eq!(played.pressed, recorded.pressed);
+        assert_eq!(played.just_pressed, recorded.just_pressed);
+        assert_eq!(played.just_released, recorded.just_released);
+        assert_eq!(played.aim, recorded.aim);
+    }
+
+    #[test]
+    fn no_aim_packs_as_zero() {
+        let input = pack_input(&action_state(&[Action::Dash], &[], Vec2::ZERO));
+        assert_eq!(input >> 24, 0);
+
+        let mut played = action_state(&[], &[], AIM_DIRECTIONS[4]);
+        unpack_input(input, &mut played);
+        assert_eq!(played.aim, Vec2::ZERO);
+    }
+
+    #[test]
+    fn pause_is_left_out_both_ways() {
+        assert_eq!(pack_input(&action_state(&[Action::Pause], &[Action::Pause], Vec2::ZERO)), 0);
+
+        // a live pause press survives a frame being played over it
+        let mut played = action_state(&[Action::Pause], &[Action::Pause], Vec2::ZERO);
+        unpack_input(pack_input(&action_state(&[Action::Jump], &[], Vec2::ZERO)), &mut played);
+        assert!(played.pressed(Action::Pause));
+        assert!(played.just_pressed(Action::Pause));
+        assert!(played.pressed(Action::Jump));
+    }
+}
@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+
+// -- RNG --
+
+// anything random in gameplay has to roll through here instead of a global rng, it's
+// reseeded at the start of every level so replays get the same numbers back
+#[derive(Resource)]
+pub struct GameRng {
+    state: u64,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> GameRng {
+        // xorshift gets stuck on zero so keep the state off it
+        GameRng {
+            state: (seed ^ 0x9e37_79b9_7f4a_7c15).max(1),
+        }
+    }
+
+    // xorshift64*, small and the same on every platform
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // from 0.0 up to but not including 1.0, off the top 24 bits since that's all an
+    // f32 can hold exactly
+    pub fn next_f32(&mut self) -> f32 {
+        ((self.next_u64() >> 40) as f32) / ((1u32 << 24) as f32)
+    }
+
+    // anywhere from min to max
+    pub fn range(&mut self, (min, max): (f32, f32)) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+}
@@ -0,0 +1,370 @@
+use bevy::prelude::*;
+
+use crate::data::{
+    delete_save_file,
+    read_save_file,
+    rename_save_file,
+    save_file_exists,
+    write_save,
+    write_save_file,
+};
+use crate::ghost::resources::{ GhostRun, Ghosts };
+use crate::level::resources::{ Campaign, CampaignProgress };
+use crate::save::resources::*;
+use crate::save::systems::unix_now;
+use crate::save::{
+    slot_file_name,
+    LEGACY_PROGRESS_FILE,
+    LEGACY_SAVE_FILE,
+    SAVE_VERSION,
+    SETTINGS_FILE,
+};
+use crate::settings::resources::Settings;
+use crate::DEFAULT_TICK_RATE;
+
+// -- SETTINGS --
+
+pub fn load_settings() -> Settings {
+    match read_save_file(SETTINGS_FILE) {
+        Some(contents) =>
+            ron::from_str(&contents).unwrap_or_else(|error| {
+                error!("couldn't read settings, using defaults: {}", error);
+                Settings::default()
+            }),
+        None => Settings::default(),
+    }
+}
+
+// -- SLOTS --
+
+// Ok(None) is an empty slot, Err is a file that's there but can't be read
+pub fn load_slot(index: usize) -> Result<Option<SlotData>, SlotError> {
+    let contents = match read_save_file(&slot_file_name(index)) {
+        Some(contents) => contents,
+        None => {
+            return Ok(None);
+        }
+    };
+
+    parse_slot(&contents).map(Some)
+}
+
+// a slot file's contents, brought up to the current version
+fn parse_slot(contents: &str) -> Result<SlotData, SlotError> {
+    let unreadable = |error: ron::error::SpannedError| SlotError::Unreadable(error.to_string());
+    let header: SaveHeader = ron::from_str(contents).map_err(unreadable)?;
+
+    match header.version {
+        SAVE_VERSION => ron::from_str(contents).map_err(unreadable),
+        3 | 4 => ron::from_str(contents).map(migrate_v4).map_err(unreadable),
+        version if version > SAVE_VERSION => Err(SlotError::Newer(version)),
+        // slots started at version 3, anything lower is a broken file
+        version =>
+            Err(SlotError::Unreadable(format!("unsupported or corrupt save version {}", version))),
+    }
+}
+
+// like load_slot, but a slot that can't be read gets backed up so playing in it
+// doesn't write over whatever was there. a slot from a newer build is the one
+// error that comes back, it's left alone so that build can still load it
+pub fn open_slot(index: usize) -> Result<Option<SlotData>, SlotError> {
+    match load_slot(index) {
+        Ok(slot) => Ok(slot),
+        Err(SlotError::Newer(version)) => Err(SlotError::Newer(version)),
+        Err(error) => {
+            error!("couldn't load save slot {}, starting fresh: {}", index + 1, error);
+            back_up_save(&slot_file_name(index));
+            Ok(None)
+        }
+    }
+}
+
+pub fn copy_slot(source: usize, target: usize) -> Result<(), String> {
+    let contents = read_save_file(&slot_file_name(source)).ok_or_else(|| {
+        format!("save slot {} is empty", source + 1)
+    })?;
+
+    write_save_file(&slot_file_name(target), &contents).map_err(|error| error.to_string())
+}
+
+pub fn delete_slot(index: usize) -> Result<(), String> {
+    delete_save_file(&slot_file_name(index)).map_err(|error| error.to_string())
+}
+
+fn back_up_save(file_name: &str) {
+    if let Err(error) = rename_save_file(file_name, &free_backup_name(file_name)) {
+        error!("couldn't back up {}: {}", file_name, error);
+    }
+}
+
+// slot_1.ron.bak, then slot_1.ron.bak2 and so on, so an older backup never gets written over
+fn free_backup_name(file_name: &str) -> String {
+    let mut backup_name = format!("{}.bak", file_name);
+    let mut count = 1;
+    while save_file_exists(&backup_name) {
+        count += 1;
+        backup_name = format!("{}.bak{}", file_name, count);
+    }
+    backup_name
+}
+
+// -- MIGRATIONS --
+
+// older builds only had the one save, it gets moved into slot 1. the old file is
+// renamed rather than deleted so it won't be migrated again if slot 1 is deleted
+pub fn migrate_legacy_saves(campaign: &Campaign) {
+    if read_save_file(&slot_file_name(0)).is_some() {
+        return;
+    }
+
+    let (file_name, migrated) = if let Some(contents) = read_save_file(LEGACY_SAVE_FILE) {
+        (LEGACY_SAVE_FILE, upgrade_v2_save(&contents, campaign))
+    } else if let Some(contents) = read_save_file(LEGACY_PROGRESS_FILE) {
+        (LEGACY_PROGRESS_FILE, upgrade_v1_progress(&contents, campaign))
+    } else {
+        return;
+    };
+    let (slot, settings) = match migrated {
+        Ok(migrated) => migrated,
+        Err(error) => {
+            error!("couldn't migrate {}: {}", file_name, error);
+            return;
+        }
+    };
+
+    info!("migrating {} to save version {}", file_name, SAVE_VERSION);
+
+    if let Err(error) = write_save(&slot_file_name(0), &slot) {
+        error!("couldn't write migrated save: {}", error);
+        return;
+    }
+    if read_save_file(SETTINGS_FILE).is_none() {
+        if let Err(error) = write_save(SETTINGS_FILE, &settings) {
+            error!("couldn't write migrated settings: {}", error);
+        }
+    }
+    if let Err(error) = rename_save_file(file_name, &format!("{}.old", file_name)) {
+        error!("couldn't move {} out of the way: {}", file_name, error);
+    }
+}
+
+// a version 1 progress.ron brought up to a current slot, with default settings
+fn upgrade_v1_progress(contents: &str, campaign: &Campaign) -> Result<(SlotData, Settings), String> {
+    let progress: CampaignProgress = ron::from_str(contents).map_err(|error| error.to_string())?;
+
+    let (slot, settings) = migrate_v2(migrate_v1(progress), campaign);
+
+    Ok((migrate_v4(slot), settings))
+}
+
+// a version 2 save.ron brought up to a current slot, and the settings it held
+fn upgrade_v2_save(contents: &str, campaign: &Campaign) -> Result<(SlotData, Settings), String> {
+    let (slot, settings) = migrate_v2(parse_v2(contents)?, campaign);
+
+    Ok((migrate_v4(slot), settings))
+}
+
+fn parse_v2(contents: &str) -> Result<SaveDataV2, String> {
+    let header: SaveHeader = ron::from_str(contents).map_err(|error| error.to_string())?;
+
+    match header.version {
+        2 => ron::from_str(contents).map_err(|error| error.to_string()),
+        version => Err(format!("expected save version 2, found {}", version)),
+    }
+}
+
+// version 1 had no settings and lived in progress.ron
+fn migrate_v1(progress: CampaignProgress) -> SaveDataV2 {
+    SaveDataV2 {
+        progress,
+        settings: Settings::default(),
+    }
+}
+
+// version 2 was a single save.ron, version 3 splits it into a slot and settings.ron
+fn migrate_v2(save: SaveDataV2, campaign: &Campaign) -> (SlotDataV4, Settings) {
+    let slot = SlotDataV4 {
+        completion: save.progress.completion(campaign),
+        progress: save.progress,
+        playtime: 0.0,
+        last_played: unix_now(),
+        ghosts: GhostsV4::default(),
+    };
+
+    (slot, save.settings)
+}
+
+// version 4 added ghosts, which version 3 files load without. version 5 keeps the tick
+// rate each ghost was recorded at, they were all at the default before
+fn migrate_v4(slot: SlotDataV4) -> SlotData {
+    let levels = slot.ghosts.levels
+        .into_iter()
+        .map(|(level, ghost_run)| {
+            let ghost_run = GhostRun {
+                geometry_hash: ghost_run.geometry_hash,
+                tick_rate: DEFAULT_TICK_RATE,
+                positions: ghost_run.positions,
+            };
+            (level, ghost_run)
+        })
+        .collect();
+
+    SlotData {
+        version: 5,
+        progress: slot.progress,
+        playtime: slot.playtime,
+        last_played: slot.last_played,
+        completion: slot.completion,
+        ghosts: Ghosts { levels },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::resources::CampaignEntry;
+
+    const V1_PROGRESS: &str =
+        r#"(
+    levels: {
+        "level_01": (unlocked: true, best_time: Some(12.5), deaths: 3, collectibles: 2),
+    },
+)"#;
+
+    const V2_SAVE: &str =
+        r#"(
+    version: 2,
+    progress: (
+        levels: {
+            "level_01": (unlocked: true, best_time: Some(12.5), deaths: 3, collectibles: 2),
+        },
+    ),
+    settings: (show_debug_render: false),
+)"#;
+
+    const V3_SLOT: &str =
+        r#"(
+    version: 3,
+    progress: (
+        levels: {
+            "level_01": (unlocked: true, best_time: Some(12.5), deaths: 3, collectibles: 2),
+        },
+    ),
+    playtime: 3600.0,
+    last_played: 1700000000,
+    completion: 75.0,
+)"#;
+
+    const V4_SLOT: &str =
+        r#"(
+    version: 4,
+    progress: (
+        levels: {
+            "level_01": (unlocked: true, best_time: Some(12.5), deaths: 3, collectibles: 2),
+        },
+    ),
+    playtime: 3600.0,
+    last_played: 1700000000,
+    completion: 75.0,
+    ghosts: (
+        levels: {
+            "level_01": (geometry_hash: 42, positions: [(0, 0), (1, 2)]),
+        },
+    ),
+)"#;
+
+    // two levels, the first with two collectibles, so finishing the first with both
+    // found is 3 of 4 towards completion
+    fn campaign() -> Campaign {
+        let entry = |id: &str, total_collectibles: u32| CampaignEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            file: format!("levels/{}.ron", id),
+            total_collectibles,
+        };
+
+        Campaign {
+            levels: vec![entry("level_01", 2), entry("level_02", 0)],
+        }
+    }
+
+    fn assert_progress_kept(slot: &SlotData) {
+        let level = slot.progress.level("level_01");
+        assert!(level.unlocked);
+        assert_eq!(level.best_time, Some(12.5));
+        assert_eq!(level.deaths, 3);
+        assert_eq!(level.collectibles, 2);
+        assert!(!slot.progress.level("level_02").unlocked);
+        assert_eq!(slot.completion, 75.0);
+    }
+
+    #[test]
+    fn v1_progress_migrates_to_current_slot() {
+        let (slot, settings) = upgrade_v1_progress(V1_PROGRESS, &campaign()).unwrap();
+
+        assert_eq!(slot.version, SAVE_VERSION);
+        assert_progress_kept(&slot);
+        assert_eq!(slot.playtime, 0.0);
+        assert!(slot.last_played > 0);
+        assert!(slot.ghosts.levels.is_empty());
+        assert_eq!(settings.show_debug_render, Settings::default().show_debug_render);
+    }
+
+    #[test]
+    fn v2_save_migrates_to_current_slot_and_keeps_settings() {
+        let (slot, settings) = upgrade_v2_save(V2_SAVE, &campaign()).unwrap();
+
+        assert_eq!(slot.version, SAVE_VERSION);
+        assert_progress_kept(&slot);
+        assert_eq!(slot.playtime, 0.0);
+        assert!(slot.ghosts.levels.is_empty());
+        assert!(!settings.show_debug_render);
+    }
+
+    #[test]
+    fn v3_slot_migrates_to_current_slot() {
+        let slot = parse_slot(V3_SLOT).unwrap();
+
+        assert_eq!(slot.version, SAVE_VERSION);
+        assert_progress_kept(&slot);
+        assert_eq!(slot.playtime, 3600.0);
+        assert_eq!(slot.last_played, 1700000000);
+        assert!(slot.ghosts.levels.is_empty());
+    }
+
+    #[test]
+    fn v4_ghosts_get_the_default_tick_rate() {
+        let slot = parse_slot(V4_SLOT).unwrap();
+
+        assert_eq!(slot.version, SAVE_VERSION);
+        assert_progress_kept(&slot);
+        let ghost_run = &slot.ghosts.levels["level_01"];
+        assert_eq!(ghost_run.geometry_hash, 42);
+        assert_eq!(ghost_run.tick_rate, DEFAULT_TICK_RATE);
+        assert_eq!(ghost_run.positions, vec![(0, 0), (1, 2)]);
+    }
+
+    #[test]
+    fn current_slot_round_trips() {
+        let slot = parse_slot(V4_SLOT).unwrap();
+        let written = ron::to_string(&slot).unwrap();
+        let read = parse_slot(&written).unwrap();
+
+        assert_eq!(read.version, SAVE_VERSION);
+        assert_progress_kept(&read);
+        assert_eq!(read.playtime, 3600.0);
+        assert_eq!(read.last_played, 1700000000);
+        assert_eq!(read.ghosts.levels["level_01"].tick_rate, DEFAULT_TICK_RATE);
+    }
+
+    #[test]
+    fn newer_and_unknown_versions_are_refused() {
+        let newer = parse_slot(&format!("(version: {})", SAVE_VERSION + 1));
+        assert!(matches!(newer, Err(SlotError::Newer(version)) if version == SAVE_VERSION + 1));
+
+        for version in [0, 1, 2] {
+            let error = parse_slot(&format!("(version: {})", version));
+            assert!(matches!(error, Err(SlotError::Unreadable(_))));
+        }
+    }
+}
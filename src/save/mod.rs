@@ -0,0 +1,42 @@
+// import bevy crates
+use bevy::prelude::*;
+
+// import modules
+pub mod file;
+pub mod resources;
+pub mod systems;
+
+// use modules
+use file::migrate_legacy_saves;
+use resources::*;
+use systems::*;
+use crate::level::resources::Campaign;
+use crate::AppState;
+
+pub const SLOT_COUNT: usize = 3;
+pub const SETTINGS_FILE: &str = "settings.ron";
+// version 2 kept a single campaign and the settings together in this file
+pub const LEGACY_SAVE_FILE: &str = "save.ron";
+// version 1 saves were just the campaign progress on its own in this file
+pub const LEGACY_PROGRESS_FILE: &str = "progress.ron";
+// bump this and add a migration in file.rs whenever SlotData changes shape
+pub const SAVE_VERSION: u32 = 5;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        // needs the campaign loaded, so this plugin has to come after LevelPlugin
+        migrate_legacy_saves(app.world.resource::<Campaign>());
+
+        app.init_resource::<ActiveSlot>()
+            .add_event::<SaveRequested>()
+            .add_systems(Update, tick_playtime.run_if(in_state(AppState::Game)))
+            .add_systems(Last, write_save_on_request);
+    }
+}
+
+// slots are numbered from 1 on disk and in menus
+pub fn slot_file_name(index: usize) -> String {
+    format!("slot_{}.ron", index + 1)
+}
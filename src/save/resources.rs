@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::{ Deserialize, Serialize };
+
+use crate::ghost::resources::Ghosts;
+use crate::level::resources::CampaignProgress;
+use crate::save::SAVE_VERSION;
+use crate::settings::resources::Settings;
+
+// -- SAVE DATA --
+
+// everything that gets written to one save slot's file
+#[derive(Serialize, Deserialize)]
+pub struct SlotData {
+    pub version: u32,
+    pub progress: CampaignProgress,
+    // seconds spent in levels
+    pub playtime: f32,
+    // unix timestamp, seconds
+    pub last_played: u64,
+    // kept here so the slot picker doesn't have to work it out
+    pub completion: f32,
+    pub ghosts: Ghosts,
+}
+
+// a slot from version 3 or 4, before ghosts kept the tick rate they were recorded at.
+// the only difference between those two is 3 had no ghosts, so they read the same
+#[derive(Deserialize)]
+pub struct SlotDataV4 {
+    pub progress: CampaignProgress,
+    pub playtime: f32,
+    pub last_played: u64,
+    pub completion: f32,
+    // added in version 4, version 3 files load with none
+    #[serde(default)]
+    pub ghosts: GhostsV4,
+}
+
+#[derive(Deserialize, Default)]
+pub struct GhostsV4 {
+    pub levels: BTreeMap<String, GhostRunV4>,
+}
+
+#[derive(Deserialize)]
+pub struct GhostRunV4 {
+    pub geometry_hash: u64,
+    pub positions: Vec<(i32, i32)>,
+}
+
+// the single save.ron from version 2, only read to migrate it into slot 1
+#[derive(Deserialize)]
+pub struct SaveDataV2 {
+    pub progress: CampaignProgress,
+    pub settings: Settings,
+}
+
+// why a slot's file couldn't be loaded
+#[derive(Debug)]
+pub enum SlotError {
+    // written by a newer build, which can still load it as long as it's left alone
+    Newer(u32),
+    Unreadable(String),
+}
+
+impl fmt::Display for SlotError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SlotError::Newer(version) =>
+                write!(formatter, "save version {} is newer than {}", version, SAVE_VERSION),
+            SlotError::Unreadable(error) => write!(formatter, "{}", error),
+        }
+    }
+}
+
+// read first so we know which version the rest of the file is
+#[derive(Deserialize)]
+pub struct SaveHeader {
+    pub version: u32,
+}
+
+// -- RESOURCES --
+
+// the slot being played, progress is written here whenever a save is requested
+#[derive(Resource, Default)]
+pub struct ActiveSlot {
+    pub index: Option<usize>,
+    pub playtime: f32,
+}
+
+// -- EVENTS --
+
+// send this whenever something worth keeping changes, the save
+// gets written once at the end of the frame
+#[derive(Event)]
+pub struct SaveRequested {}
@@ -0,0 +1,56 @@
+use std::time::{ SystemTime, UNIX_EPOCH };
+
+use bevy::prelude::*;
+
+use crate::data::write_save;
+use crate::ghost::resources::Ghosts;
+use crate::level::resources::{ Campaign, CampaignProgress };
+use crate::save::resources::*;
+use crate::save::{ slot_file_name, SAVE_VERSION, SETTINGS_FILE };
+use crate::settings::resources::Settings;
+
+pub fn tick_playtime(mut active_slot: ResMut<ActiveSlot>, time: Res<Time>) {
+    if active_slot.index.is_some() {
+        active_slot.playtime += time.delta_seconds();
+    }
+}
+
+pub fn write_save_on_request(
+    mut save_requested_event_reader: EventReader<SaveRequested>,
+    active_slot: Res<ActiveSlot>,
+    campaign: Res<Campaign>,
+    progress: Res<CampaignProgress>,
+    ghosts: Res<Ghosts>,
+    settings: Res<Settings>
+) {
+    // any number of requests in a frame is still just one write
+    if save_requested_event_reader.read().count() == 0 {
+        return;
+    }
+
+    if let Err(error) = write_save(SETTINGS_FILE, &*settings) {
+        error!("couldn't write settings: {}", error);
+    }
+
+    if let Some(index) = active_slot.index {
+        let slot = SlotData {
+            version: SAVE_VERSION,
+            progress: progress.clone(),
+            playtime: active_slot.playtime,
+            last_played: unix_now(),
+            completion: progress.completion(&campaign),
+            ghosts: ghosts.clone(),
+        };
+
+        if let Err(error) = write_save(&slot_file_name(index), &slot) {
+            error!("couldn't write save slot {}: {}", index + 1, error);
+        }
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
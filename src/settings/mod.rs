@@ -0,0 +1,22 @@
+// import bevy crates
+use bevy::prelude::*;
+
+// import modules
+pub mod resources;
+pub mod systems;
+
+// use modules
+use resources::*;
+use systems::*;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        // main() normally inserts these already, loaded from settings.ron
+        app.init_resource::<Settings>().add_systems(
+            Update,
+            apply_settings.run_if(resource_changed::<Settings>)
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+use bevy::window::{ PresentMode, WindowMode };
+use serde::{ Deserialize, Serialize };
+
+use crate::controls::resources::InputMap;
+use crate::{ DEFAULT_TICK_RATE, WINDOW_HEIGHT, WINDOW_WIDTH };
+
+// resolutions the settings menu cycles through
+pub const RESOLUTIONS: [(u32, u32); 5] = [
+    (1024, 720),
+    (1280, 720),
+    (1366, 768),
+    (1600, 900),
+    (1920, 1080),
+];
+// most hits the assist health setting goes up to
+pub const MAX_ASSIST_HEALTH: u32 = 5;
+
+// -- RESOURCES --
+
+// player-facing options, kept in settings.ron and shared by every save slot.
+// missing fields fall back to their defaults so older files still load
+#[derive(Resource, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Settings {
+    pub window_mode: WindowModeSetting,
+    pub resolution: (u32, u32),
+    pub vsync: bool,
+    // volumes are 0.0 to 1.0, music and sfx get multiplied by master
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    // multiplier on screen shake, 0.0 turns it off
+    pub screen_shake: f32,
+    // rapier's hitbox overlay
+    pub show_debug_render: bool,
+    // the see-through best run that plays alongside the player
+    pub show_ghost: bool,
+    // in-game timer and splits, running from the start of the first level
+    pub speedrun_timer: bool,
+    // hits the player can take before dying, 0 is off and anything that hurts kills
+    pub assist_health: u32,
+    pub bindings: InputMap,
+    // how far a stick has to move before it counts, 0.0 to 1.0
+    pub stick_deadzone: f32,
+    // simulation ticks a second. not in the menu, replays and records from one rate
+    // don't carry over to another
+    pub tick_rate: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            window_mode: WindowModeSetting::Windowed,
+            resolution: (WINDOW_WIDTH as u32, WINDOW_HEIGHT as u32),
+            vsync: true,
+            master_volume: 1.0,
+            music_volume: 0.8,
+            sfx_volume: 1.0,
+            screen_shake: 1.0,
+            show_debug_render: true,
+            show_ghost: true,
+            speedrun_timer: false,
+            assist_health: 0,
+            bindings: InputMap::default(),
+            stick_deadzone: 0.3,
+            tick_rate: DEFAULT_TICK_RATE,
+        }
+    }
+}
+
+impl Settings {
+    pub fn apply_to_window(&self, window: &mut Window) {
+        window.mode = match self.window_mode {
+            WindowModeSetting::Windowed => WindowMode::Windowed,
+            WindowModeSetting::Borderless => WindowMode::BorderlessFullscreen,
+            WindowModeSetting::Fullscreen => WindowMode::Fullscreen,
+        };
+        window.resolution.set(self.resolution.0 as f32, self.resolution.1 as f32);
+        window.present_mode = if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        };
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum WindowModeSetting {
+    #[default]
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
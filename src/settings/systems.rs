@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_rapier2d::prelude::*;
+
+use crate::settings::resources::*;
+
+// runs whenever Settings changes so the menu's changes show up right away
+pub fn apply_settings(
+    settings: Res<Settings>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    mut debug_render_context: ResMut<DebugRenderContext>
+) {
+    if let Ok(mut window) = window_query.get_single_mut() {
+        settings.apply_to_window(&mut window);
+    }
+
+    debug_render_context.enabled = settings.show_debug_render;
+}
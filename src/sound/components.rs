@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+
+// -- COMPONENTS --
+
+// a looping music track or stem, its volume is set every frame from the fades, the
+// mix and the settings
+#[derive(Component)]
+pub struct MusicTrack {
+    // path under assets/, so the same track carries on between levels that share it
+    pub file: String,
+    // stems follow a MusicParameters value, the main track is always all the way up
+    pub parameter: Option<String>,
+    pub source: Handle<AudioSource>,
+    // it's only given something to play once every new track for the level has loaded,
+    // so stems start together and stay lined up
+    pub started: bool,
+    // 0.0 to 1.0, in and out between levels
+    pub fade: f32,
+    // 0.0 to 1.0, following the parameter
+    pub level: f32,
+    // despawned once it's faded all the way out
+    pub fading_out: bool,
+}
+
+// sensor that sets a music parameter while the player's inside it
+#[derive(Component)]
+pub struct MusicTrigger {
+    pub parameter: String,
+    pub value: f32,
+}
+
+// one-shot music cue, the music ducks under it until it's done
+#[derive(Component)]
+pub struct MusicStinger {}
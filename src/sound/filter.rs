@@ -0,0 +1,91 @@
+use std::f32::consts::TAU;
+use std::sync::atomic::{ AtomicU32, Ordering };
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy::audio::{ Decodable, Source };
+use bevy::prelude::*;
+
+use crate::sound::OPEN_CUTOFF;
+
+// -- FILTERED AUDIO --
+
+// a sound played through a low-pass filter. the cutoff is shared with MusicMix so it
+// can be moved while the sound's playing, bevy doesn't give sinks any filters of its own
+#[derive(Asset, TypePath)]
+pub struct FilteredAudio {
+    pub source: AudioSource,
+    // cutoff in hz, as f32 bits
+    pub cutoff: Arc<AtomicU32>,
+}
+
+impl Decodable for FilteredAudio {
+    type DecoderItem = <AudioSource as Decodable>::DecoderItem;
+    type Decoder = LowPass<<AudioSource as Decodable>::Decoder>;
+
+    fn decoder(&self) -> Self::Decoder {
+        LowPass::new(self.source.decoder(), self.cutoff.clone())
+    }
+}
+
+// one pole low-pass, soft but cheap, and the cutoff can change every sample without
+// clicking
+pub struct LowPass<S> {
+    source: S,
+    cutoff: Arc<AtomicU32>,
+    // last output on each channel
+    previous: Vec<f32>,
+    channel: usize,
+}
+
+impl<S: Source<Item = i16>> LowPass<S> {
+    fn new(source: S, cutoff: Arc<AtomicU32>) -> LowPass<S> {
+        let channels = source.channels().max(1) as usize;
+        LowPass {
+            source,
+            cutoff,
+            previous: vec![0.0; channels],
+            channel: 0,
+        }
+    }
+}
+
+impl<S: Source<Item = i16>> Iterator for LowPass<S> {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<i16> {
+        let sample = self.source.next()? as f32;
+        let channel = self.channel;
+        self.channel = (self.channel + 1) % self.previous.len();
+
+        let cutoff = f32::from_bits(self.cutoff.load(Ordering::Relaxed));
+        // wide open it's left alone, but still followed so closing it later doesn't pop
+        let output = if cutoff >= OPEN_CUTOFF {
+            sample
+        } else {
+            let blend = 1.0 - (-TAU * cutoff / (self.source.sample_rate() as f32)).exp();
+            self.previous[channel] + (sample - self.previous[channel]) * blend
+        };
+        self.previous[channel] = output;
+
+        Some(output as i16)
+    }
+}
+
+impl<S: Source<Item = i16>> Source for LowPass<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+}
@@ -0,0 +1,70 @@
+// import bevy crates
+use bevy::audio::AddAudioSource;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::PhysicsSet;
+
+// import modules
+pub mod components;
+pub mod filter;
+pub mod resources;
+pub mod systems;
+
+// use modules
+use filter::FilteredAudio;
+use resources::*;
+use systems::*;
+use crate::data::load_ron;
+use crate::level::systems::spawn_current_level;
+use crate::{ simulation_running, AppState };
+
+pub const SOUNDS_FILE: &str = "sounds/sounds.ron";
+pub const CHECKPOINT_STINGER_FILE: &str = "music/checkpoint_stinger.wav";
+// seconds for one track to fade out and the next to fade in
+pub const MUSIC_FADE_TIME: f32 = 1.5;
+// seconds for a stem to come in or drop out when its parameter changes
+pub const STEM_FADE_TIME: f32 = 0.75;
+// how loud the music stays while a stinger plays over it
+pub const STINGER_DUCK: f32 = 0.4;
+// low-pass cutoffs in hz. anything at or above open isn't filtered at all
+pub const OPEN_CUTOFF: f32 = 20000.0;
+pub const PAUSED_CUTOFF: f32 = 700.0;
+// roughly how long the filter takes to sweep between the two
+pub const FILTER_SWEEP_TIME: f32 = 0.2;
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        let sound_effects: SoundEffects = load_ron(SOUNDS_FILE).unwrap();
+
+        app.add_audio_source::<FilteredAudio>()
+            .insert_resource(sound_effects)
+            .init_resource::<SfxCooldowns>()
+            .init_resource::<SoundRng>()
+            .init_resource::<LevelMusic>()
+            .init_resource::<MusicParameters>()
+            .init_resource::<MusicMix>()
+            .init_resource::<MusicStingers>()
+            .add_event::<PlaySfx>()
+            .add_systems(Startup, (load_sound_effects, load_music_stingers))
+            .add_systems(OnEnter(AppState::Game), start_level_music.after(spawn_current_level))
+            .add_systems(OnEnter(AppState::MainMenu), stop_music)
+            .add_systems(
+                FixedUpdate,
+                player_in_music_triggers
+                    .before(PhysicsSet::SyncBackend)
+                    .run_if(simulation_running)
+            )
+            // menus make sounds too so these run all the time
+            .add_systems(
+                Update,
+                (
+                    play_sfx,
+                    play_checkpoint_stinger,
+                    start_loaded_music,
+                    update_music_mix,
+                    crossfade_music.after(start_loaded_music).after(update_music_mix),
+                )
+            );
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::sync::atomic::AtomicU32;
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::rng::GameRng;
+use crate::sound::OPEN_CUTOFF;
+
+// -- RESOURCES --
+
+#[derive(Resource, Deserialize)]
+pub struct SoundEffects {
+    pub sounds: HashMap<SfxId, SfxData>,
+}
+
+// when each sound last played, seconds since startup
+#[derive(Resource, Default)]
+pub struct SfxCooldowns {
+    pub last_played: HashMap<SfxId, f32>,
+}
+
+// sounds get their own rng for the same reason particles do, GameRng is for replays
+#[derive(Resource)]
+pub struct SoundRng {
+    pub rng: GameRng,
+}
+
+impl Default for SoundRng {
+    fn default() -> SoundRng {
+        SoundRng { rng: GameRng::new(0) }
+    }
+}
+
+// named values the level sets through its music triggers, 0.0 to 1.0. each stem is as
+// loud as its parameter, so "chase" at 1.0 brings the chase stem all the way in
+#[derive(Resource, Default)]
+pub struct MusicParameters {
+    pub values: HashMap<String, f32>,
+}
+
+impl MusicParameters {
+    // anything that's never been set is off
+    pub fn get(&self, name: &str) -> f32 {
+        self.values.get(name).copied().unwrap_or(0.0)
+    }
+}
+
+// what's applied on top of every music track
+#[derive(Resource)]
+pub struct MusicMix {
+    // low-pass cutoff in hz as f32 bits, shared with every FilteredAudio
+    pub cutoff: Arc<AtomicU32>,
+    // volume multiplier, dips while a stinger plays
+    pub duck: f32,
+}
+
+impl Default for MusicMix {
+    fn default() -> MusicMix {
+        MusicMix {
+            cutoff: Arc::new(AtomicU32::new(OPEN_CUTOFF.to_bits())),
+            duck: 1.0,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct MusicStingers {
+    pub checkpoint: Handle<AudioSource>,
+}
+
+// -- LEVEL DATA --
+
+// what the current level wants playing, no file fades whatever's on out to silence
+#[derive(Resource, Default)]
+pub struct LevelMusic {
+    pub file: Option<String>,
+    pub stems: Vec<MusicStemData>,
+}
+
+// a layer that loops alongside the level's music, it has to be the same length
+#[derive(Deserialize, Clone)]
+pub struct MusicStemData {
+    // path under assets/
+    pub file: String,
+    pub parameter: String,
+}
+
+// sets a music parameter while the player's inside, sizes are full width/height and
+// positions are centers
+#[derive(Deserialize, Clone)]
+pub struct MusicTriggerData {
+    pub size: (f32, f32),
+    pub position: (f32, f32),
+    pub parameter: String,
+    pub value: f32,
+}
+
+// -- SOUND DATA --
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SfxId {
+    Jump,
+    Dash,
+    Land,
+    Death,
+    Hurt,
+    Pickup,
+    Stomp,
+    MenuMove,
+    MenuSelect,
+    MenuBack,
+}
+
+#[derive(Deserialize)]
+pub struct SfxData {
+    // path under assets/
+    pub file: String,
+    // before the settings volumes get applied, 0.0 to 1.0
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+    // how far the pitch can wander either way each time it plays, 0.1 is up to 10%
+    #[serde(default)]
+    pub pitch_variation: f32,
+    // seconds before it can play again, anything sooner gets dropped
+    #[serde(default)]
+    pub cooldown: f32,
+    // filled in once the asset server's loading it
+    #[serde(skip)]
+    pub handle: Handle<AudioSource>,
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+// -- EVENTS --
+
+#[derive(Event)]
+pub struct PlaySfx(pub SfxId);
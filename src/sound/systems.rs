@@ -0,0 +1,274 @@
+use std::sync::atomic::Ordering;
+
+use bevy::asset::LoadState;
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::level::resources::CheckpointReached;
+use crate::settings::resources::Settings;
+use crate::sound::components::*;
+use crate::sound::filter::FilteredAudio;
+use crate::sound::resources::*;
+use crate::sound::{
+    CHECKPOINT_STINGER_FILE,
+    FILTER_SWEEP_TIME,
+    MUSIC_FADE_TIME,
+    OPEN_CUTOFF,
+    PAUSED_CUTOFF,
+    STEM_FADE_TIME,
+    STINGER_DUCK,
+};
+use crate::{ AppState, Player, SimulationState };
+
+// -- SOUND EFFECTS --
+
+// loaded up front so nothing's left waiting on the disk the first time it plays
+pub fn load_sound_effects(asset_server: Res<AssetServer>, mut sound_effects: ResMut<SoundEffects>) {
+    for sound in sound_effects.sounds.values_mut() {
+        sound.handle = asset_server.load(sound.file.clone());
+    }
+}
+
+pub fn play_sfx(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    sound_effects: Res<SoundEffects>,
+    mut cooldowns: ResMut<SfxCooldowns>,
+    mut sound_rng: ResMut<SoundRng>,
+    mut play_sfx_event_reader: EventReader<PlaySfx>
+) {
+    let now = time.elapsed_seconds();
+
+    for PlaySfx(id) in play_sfx_event_reader.read() {
+        let Some(sound) = sound_effects.sounds.get(id) else {
+            continue;
+        };
+        // the same sound stacked on itself just comes out louder and muddier
+        let cooling_down = cooldowns.last_played
+            .get(id)
+            .is_some_and(|last_played| now - last_played < sound.cooldown);
+        if cooling_down {
+            continue;
+        }
+        cooldowns.last_played.insert(*id, now);
+
+        let volume = sound.volume * settings.master_volume * settings.sfx_volume;
+        if volume <= 0.0 {
+            continue;
+        }
+        let variation = sound.pitch_variation;
+        let speed = 1.0 + sound_rng.rng.range((-variation, variation));
+
+        commands.spawn(AudioBundle {
+            source: sound.handle.clone(),
+            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(volume)).with_speed(speed),
+        });
+    }
+}
+
+// -- MUSIC --
+
+// keeps tracks going if the new level uses the same ones, otherwise crossfades over
+pub fn start_level_music(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    level_music: Res<LevelMusic>,
+    mut music_parameters: ResMut<MusicParameters>,
+    mut music_query: Query<&mut MusicTrack>
+) {
+    music_parameters.values.clear();
+
+    let mut wanted: Vec<(String, Option<String>)> = Vec::new();
+    if let Some(file) = &level_music.file {
+        wanted.push((file.clone(), None));
+        for stem in level_music.stems.iter() {
+            wanted.push((stem.file.clone(), Some(stem.parameter.clone())));
+        }
+    }
+
+    for mut track in music_query.iter_mut() {
+        let playing = wanted
+            .iter()
+            .position(|(file, parameter)| *file == track.file && *parameter == track.parameter);
+        match playing {
+            // could be partway through fading out, it just comes back in from there
+            Some(index) => {
+                track.fading_out = false;
+                wanted.remove(index);
+            }
+            None => {
+                track.fading_out = true;
+            }
+        }
+    }
+
+    for (file, parameter) in wanted {
+        commands.spawn(MusicTrack {
+            source: asset_server.load(file.clone()),
+            file,
+            parameter,
+            started: false,
+            fade: 0.0,
+            level: 0.0,
+            fading_out: false,
+        });
+    }
+}
+
+// stems only line up if they all start on the same frame, so nothing starts until
+// everything that's waiting has loaded
+pub fn start_loaded_music(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    audio_sources: Res<Assets<AudioSource>>,
+    mut filtered_audio: ResMut<Assets<FilteredAudio>>,
+    music_mix: Res<MusicMix>,
+    mut music_query: Query<(Entity, &mut MusicTrack)>
+) {
+    let mut waiting = false;
+    for (_, mut track) in music_query.iter_mut() {
+        if track.started || audio_sources.contains(&track.source) {
+            continue;
+        }
+        if let Some(LoadState::Failed) = asset_server.get_load_state(&track.source) {
+            warn!("couldn't load music {}", track.file);
+            track.started = true;
+        } else {
+            waiting = true;
+        }
+    }
+    if waiting {
+        return;
+    }
+
+    for (entity, mut track) in music_query.iter_mut() {
+        if track.started {
+            continue;
+        }
+        track.started = true;
+
+        if let Some(source) = audio_sources.get(&track.source) {
+            let filtered = filtered_audio.add(FilteredAudio {
+                source: source.clone(),
+                cutoff: music_mix.cutoff.clone(),
+            });
+            commands.entity(entity).insert(AudioSourceBundle {
+                source: filtered,
+                settings: PlaybackSettings::LOOP.with_volume(Volume::new(0.0)),
+            });
+        }
+    }
+}
+
+pub fn stop_music(mut music_query: Query<&mut MusicTrack>) {
+    for mut track in music_query.iter_mut() {
+        track.fading_out = true;
+    }
+}
+
+pub fn crossfade_music(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    music_parameters: Res<MusicParameters>,
+    music_mix: Res<MusicMix>,
+    mut music_query: Query<(Entity, &mut MusicTrack, Option<&AudioSink>)>
+) {
+    let fade_step = time.delta_seconds() / MUSIC_FADE_TIME;
+    let stem_step = time.delta_seconds() / STEM_FADE_TIME;
+
+    for (entity, mut track, sink) in music_query.iter_mut() {
+        track.fade = if track.fading_out {
+            (track.fade - fade_step).max(0.0)
+        } else {
+            (track.fade + fade_step).min(1.0)
+        };
+
+        if track.fading_out && track.fade <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let target = match &track.parameter {
+            Some(parameter) => music_parameters.get(parameter).clamp(0.0, 1.0),
+            None => 1.0,
+        };
+        track.level += (target - track.level).clamp(-stem_step, stem_step);
+
+        // the sink only turns up once the track's loaded and started
+        if let Some(sink) = sink {
+            let volume = settings.master_volume * settings.music_volume;
+            sink.set_volume(track.fade * track.level * music_mix.duck * volume);
+        }
+    }
+}
+
+// muffles the music while paused and ducks it under stingers
+pub fn update_music_mix(
+    time: Res<Time>,
+    app_state: Res<State<AppState>>,
+    simulation_state: Res<State<SimulationState>>,
+    stinger_query: Query<(), With<MusicStinger>>,
+    mut music_mix: ResMut<MusicMix>
+) {
+    let paused =
+        *app_state.get() == AppState::Game && *simulation_state.get() == SimulationState::Paused;
+    let target = if paused { PAUSED_CUTOFF } else { OPEN_CUTOFF };
+    let blend = (time.delta_seconds() / FILTER_SWEEP_TIME).min(1.0);
+
+    // swept in octaves rather than hz so it sounds even all the way down
+    let cutoff = f32::from_bits(music_mix.cutoff.load(Ordering::Relaxed));
+    let mut swept = (cutoff.ln() + (target.ln() - cutoff.ln()) * blend).exp();
+    if (swept - target).abs() < target * 0.01 {
+        swept = target;
+    }
+    music_mix.cutoff.store(swept.to_bits(), Ordering::Relaxed);
+
+    let duck = if stinger_query.is_empty() { 1.0 } else { STINGER_DUCK };
+    music_mix.duck += (duck - music_mix.duck) * blend;
+}
+
+pub fn player_in_music_triggers(
+    rapier_context: Res<RapierContext>,
+    player_query: Query<Entity, With<Player>>,
+    trigger_query: Query<(Entity, &MusicTrigger)>,
+    mut music_parameters: ResMut<MusicParameters>
+) {
+    if let Ok(player) = player_query.get_single() {
+        for (entity, trigger) in trigger_query.iter() {
+            let inside = rapier_context.intersection_pair(player, entity) == Some(true);
+            if inside && music_parameters.get(&trigger.parameter) != trigger.value {
+                music_parameters.values.insert(trigger.parameter.clone(), trigger.value);
+            }
+        }
+    }
+}
+
+// -- STINGERS --
+
+pub fn load_music_stingers(
+    asset_server: Res<AssetServer>,
+    mut music_stingers: ResMut<MusicStingers>
+) {
+    music_stingers.checkpoint = asset_server.load(CHECKPOINT_STINGER_FILE);
+}
+
+pub fn play_checkpoint_stinger(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    music_stingers: Res<MusicStingers>,
+    mut checkpoint_reached_event_reader: EventReader<CheckpointReached>
+) {
+    if checkpoint_reached_event_reader.read().last().is_some() {
+        let volume = settings.master_volume * settings.music_volume;
+        commands.spawn((
+            AudioBundle {
+                source: music_stingers.checkpoint.clone(),
+                settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(volume)),
+            },
+            MusicStinger {},
+        ));
+    }
+}
@@ -0,0 +1,16 @@
+use bevy::prelude::*;
+
+// -- COMPONENTS --
+
+#[derive(Component)]
+pub struct SpeedrunHud {}
+
+#[derive(Component)]
+pub struct SpeedrunTimerText {}
+
+// one level's split in the hud, index into Campaign.levels. sections are
+// the level name, the split time and the delta against the personal best
+#[derive(Component)]
+pub struct SplitRow {
+    pub index: usize,
+}
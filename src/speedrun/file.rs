@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+
+use crate::data::{ read_save_file, save_dir, write_save, write_save_file };
+use crate::level::resources::Campaign;
+use crate::speedrun::resources::*;
+use crate::speedrun::{ RECORDS_FILE, SPLITS_FILE };
+
+// -- RECORDS --
+
+pub fn load_records() -> SpeedrunRecords {
+    match read_save_file(RECORDS_FILE) {
+        Some(contents) =>
+            ron::from_str(&contents).unwrap_or_else(|error| {
+                error!("couldn't read speedrun records, starting fresh: {}", error);
+                SpeedrunRecords::default()
+            }),
+        None => SpeedrunRecords::default(),
+    }
+}
+
+pub fn write_records(records: &SpeedrunRecords) {
+    if let Err(error) = write_save(RECORDS_FILE, records) {
+        error!("couldn't write speedrun records: {}", error);
+    }
+}
+
+// -- LIVESPLIT --
+
+// writes the personal best as a LiveSplit splits file. the times go in as game
+// time, since that's what the in-game timer is
+pub fn write_lss(records: &SpeedrunRecords, campaign: &Campaign) {
+    let mut lss = String::new();
+    lss.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    lss.push_str("<Run version=\"1.7.0\">\n");
+    lss.push_str("  <GameIcon />\n");
+    lss.push_str("  <GameName>gumperjame</GameName>\n");
+    lss.push_str("  <CategoryName>Any%</CategoryName>\n");
+    lss.push_str("  <Metadata>\n");
+    lss.push_str("    <Run id=\"\" />\n");
+    lss.push_str("    <Platform usesEmulator=\"False\"></Platform>\n");
+    lss.push_str("    <Region />\n");
+    lss.push_str("    <Variables />\n");
+    lss.push_str("  </Metadata>\n");
+    lss.push_str("  <Offset>00:00:00</Offset>\n");
+    lss.push_str(&format!("  <AttemptCount>{}</AttemptCount>\n", records.attempts));
+    lss.push_str("  <AttemptHistory />\n");
+    lss.push_str("  <Segments>\n");
+
+    for (index, entry) in campaign.levels.iter().enumerate() {
+        lss.push_str("    <Segment>\n");
+        lss.push_str(&format!("      <Name>{}</Name>\n", escape_xml(&entry.name)));
+        lss.push_str("      <Icon />\n");
+        lss.push_str("      <SplitTimes>\n");
+        if let Some(ticks) = records.personal_best.get(index) {
+            lss.push_str("        <SplitTime name=\"Personal Best\">\n");
+            let time = lss_time(*ticks, records.tick_rate);
+            lss.push_str(&format!("          <GameTime>{}</GameTime>\n", time));
+            lss.push_str("        </SplitTime>\n");
+        } else {
+            lss.push_str("        <SplitTime name=\"Personal Best\" />\n");
+        }
+        lss.push_str("      </SplitTimes>\n");
+        match records.best_segments.get(index) {
+            Some(ticks) => {
+                lss.push_str("      <BestSegmentTime>\n");
+                let time = lss_time(*ticks, records.tick_rate);
+                lss.push_str(&format!("        <GameTime>{}</GameTime>\n", time));
+                lss.push_str("      </BestSegmentTime>\n");
+            }
+            None => lss.push_str("      <BestSegmentTime />\n"),
+        }
+        lss.push_str("      <SegmentHistory />\n");
+        lss.push_str("    </Segment>\n");
+    }
+
+    lss.push_str("  </Segments>\n");
+    lss.push_str("  <AutoSplitterSettings />\n");
+    lss.push_str("</Run>\n");
+
+    match write_save_file(SPLITS_FILE, &lss) {
+        Ok(()) => info!("wrote splits to {}", save_dir().join(SPLITS_FILE).display()),
+        Err(error) => error!("couldn't write splits: {}", error),
+    }
+}
+
+// LiveSplit wants hh:mm:ss.fffffff
+fn lss_time(ticks: u32, tick_rate: u32) -> String {
+    let seconds = (ticks as f64) / (tick_rate as f64);
+    let whole_seconds = seconds.floor() as u64;
+    let fraction = ((seconds - (whole_seconds as f64)) * 10_000_000.0).round() as u64;
+
+    format!(
+        "{:02}:{:02}:{:02}.{:07}",
+        whole_seconds / 3600,
+        (whole_seconds / 60) % 60,
+        whole_seconds % 60,
+        fraction.min(9_999_999)
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
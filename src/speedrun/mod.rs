@@ -0,0 +1,43 @@
+// import bevy crates
+use bevy::prelude::*;
+
+// import modules
+pub mod components;
+pub mod file;
+pub mod resources;
+pub mod systems;
+
+// use modules
+use file::load_records;
+use resources::*;
+use systems::*;
+use crate::level::systems::player_reaches_exit;
+use crate::{ simulation_running, AppState };
+
+pub const RECORDS_FILE: &str = "speedrun.ron";
+// the personal best, written out for LiveSplit whenever a run finishes
+pub const SPLITS_FILE: &str = "splits.lss";
+pub const AHEAD_COLOR: Color = Color::GREEN;
+pub const BEHIND_COLOR: Color = Color::RED;
+// fastest this level has ever been done, whatever the rest of the run was like
+pub const BEST_SEGMENT_COLOR: Color = Color::GOLD;
+pub const UPCOMING_COLOR: Color = Color::GRAY;
+
+pub struct SpeedrunPlugin;
+
+impl Plugin for SpeedrunPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_records())
+            .init_resource::<SpeedrunRun>()
+            .add_systems(OnEnter(AppState::Game), start_or_continue_run)
+            .add_systems(OnEnter(AppState::LevelResults), split)
+            .add_systems(OnEnter(AppState::MainMenu), end_run)
+            // counts the same ticks the player moves on, so pauses and
+            // the screens between levels never show up in the time
+            .add_systems(
+                FixedUpdate,
+                tick_speedrun.before(player_reaches_exit).run_if(simulation_running)
+            )
+            .add_systems(Update, update_speedrun_hud);
+    }
+}
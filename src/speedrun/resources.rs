@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use serde::{ Deserialize, Serialize };
+
+use crate::DEFAULT_TICK_RATE;
+
+// -- RESOURCES --
+
+// personal best and best segments, kept in speedrun.ron and shared by every save slot.
+// times are all in simulation ticks so they come out the same on every machine
+#[derive(Resource, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SpeedrunRecords {
+    // level ids the times below line up with, records from a different campaign get dropped
+    pub levels: Vec<String>,
+    // ticks a second the times were counted at, the same goes for a different rate.
+    // files from before this was kept were all at the default. speedrun.ron has no
+    // version like the slots do, anything missing just falls back the same way the
+    // whole struct does, since the worst that can happen is losing some records
+    #[serde(default = "default_tick_rate")]
+    pub tick_rate: u32,
+    pub attempts: u32,
+    // ticks from the start of the best run to the end of each level
+    pub personal_best: Vec<u32>,
+    // fewest ticks each level has ever taken on its own
+    pub best_segments: Vec<u32>,
+}
+
+// the run in progress, a run starts whenever the first level is started with
+// the speedrun timer on and goes until the menu
+#[derive(Resource, Default)]
+pub struct SpeedrunRun {
+    pub active: bool,
+    pub tick_rate: u32,
+    pub ticks: u32,
+    // ticks at the end of each level finished so far
+    pub splits: Vec<u32>,
+    // the records from when the run started, so the deltas don't shift mid-run
+    pub personal_best: Vec<u32>,
+    pub best_segments: Vec<u32>,
+}
+
+impl SpeedrunRun {
+    // how long the level at index took on its own
+    pub fn segment(&self, index: usize) -> Option<u32> {
+        let end = *self.splits.get(index)?;
+        let start = if index == 0 { 0 } else { self.splits[index - 1] };
+        Some(end - start)
+    }
+}
+
+fn default_tick_rate() -> u32 {
+    DEFAULT_TICK_RATE
+}
+
+pub fn ticks_to_seconds(ticks: u32, tick_rate: u32) -> f32 {
+    (ticks as f32) / (tick_rate as f32)
+}
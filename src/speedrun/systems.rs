@@ -0,0 +1,219 @@
+use bevy::prelude::*;
+
+use crate::level::resources::{ Campaign, CurrentLevel };
+use crate::level::systems::format_time;
+use crate::settings::resources::Settings;
+use crate::speedrun::components::*;
+use crate::speedrun::file::{ write_lss, write_records };
+use crate::speedrun::resources::*;
+use crate::speedrun::{ AHEAD_COLOR, BEHIND_COLOR, BEST_SEGMENT_COLOR, UPCOMING_COLOR };
+use crate::TickRate;
+
+// -- RUNS --
+
+// starting the first level starts a run, every level after it carries on with the
+// same one as long as nothing's been skipped
+pub fn start_or_continue_run(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    campaign: Res<Campaign>,
+    current_level: Res<CurrentLevel>,
+    tick_rate: Res<TickRate>,
+    mut run: ResMut<SpeedrunRun>,
+    mut records: ResMut<SpeedrunRecords>,
+    hud_query: Query<Entity, With<SpeedrunHud>>
+) {
+    if run.active && run.splits.len() == current_level.index {
+        return;
+    }
+
+    for entity in hud_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    run.active = false;
+
+    if !settings.speedrun_timer || current_level.index != 0 {
+        return;
+    }
+
+    // times from a different set of levels or tick rate can't be compared against
+    let level_ids: Vec<String> = campaign.levels
+        .iter()
+        .map(|entry| entry.id.clone())
+        .collect();
+    if records.levels != level_ids || records.tick_rate != tick_rate.hz {
+        *records = SpeedrunRecords {
+            levels: level_ids,
+            tick_rate: tick_rate.hz,
+            ..default()
+        };
+    }
+    records.attempts += 1;
+    write_records(&records);
+
+    *run = SpeedrunRun {
+        active: true,
+        tick_rate: tick_rate.hz,
+        ticks: 0,
+        splits: Vec::new(),
+        personal_best: records.personal_best.clone(),
+        best_segments: records.best_segments.clone(),
+    };
+
+    spawn_speedrun_hud(&mut commands, &campaign);
+}
+
+pub fn tick_speedrun(mut run: ResMut<SpeedrunRun>) {
+    if run.active {
+        run.ticks += 1;
+    }
+}
+
+pub fn split(
+    campaign: Res<Campaign>,
+    mut run: ResMut<SpeedrunRun>,
+    mut records: ResMut<SpeedrunRecords>
+) {
+    if !run.active {
+        return;
+    }
+
+    let ticks = run.ticks;
+    run.splits.push(ticks);
+    let index = run.splits.len() - 1;
+
+    if let Some(segment) = run.segment(index) {
+        match records.best_segments.get_mut(index) {
+            Some(best_segment) => {
+                *best_segment = (*best_segment).min(segment);
+            }
+            None => records.best_segments.push(segment),
+        }
+    }
+
+    if run.splits.len() == campaign.levels.len() {
+        let is_personal_best = records.personal_best
+            .last()
+            .is_none_or(|best| ticks < *best);
+        if is_personal_best {
+            info!("new personal best: {}", format_time(ticks_to_seconds(ticks, run.tick_rate)));
+            records.personal_best = run.splits.clone();
+        }
+        write_lss(&records, &campaign);
+    }
+
+    write_records(&records);
+}
+
+pub fn end_run(
+    mut commands: Commands,
+    mut run: ResMut<SpeedrunRun>,
+    hud_query: Query<Entity, With<SpeedrunHud>>
+) {
+    run.active = false;
+    for entity in hud_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// -- HUD --
+
+fn spawn_speedrun_hud(commands: &mut Commands, campaign: &Campaign) {
+    let text_style = TextStyle { font_size: 20.0, ..default() };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(12.0),
+                    right: Val::Px(12.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::FlexEnd,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                // over the results screen too, so the last split can be seen
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            SpeedrunHud {},
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section("", TextStyle { font_size: 32.0, ..default() }),
+                SpeedrunTimerText {},
+            ));
+
+            for (index, entry) in campaign.levels.iter().enumerate() {
+                parent.spawn((
+                    TextBundle::from_sections([
+                        TextSection::new(format!("{}    ", entry.name), text_style.clone()),
+                        TextSection::new("", text_style.clone()),
+                        TextSection::new("", text_style.clone()),
+                    ]),
+                    SplitRow { index },
+                ));
+            }
+        });
+}
+
+pub fn update_speedrun_hud(
+    run: Res<SpeedrunRun>,
+    mut timer_query: Query<&mut Text, With<SpeedrunTimerText>>,
+    mut split_query: Query<(&mut Text, &SplitRow), Without<SpeedrunTimerText>>
+) {
+    if !run.is_changed() {
+        return;
+    }
+
+    if let Ok(mut text) = timer_query.get_single_mut() {
+        text.sections[0].value = format_time(ticks_to_seconds(run.ticks, run.tick_rate));
+    }
+
+    for (mut text, row) in split_query.iter_mut() {
+        let personal_best = run.personal_best.get(row.index).copied();
+
+        match run.splits.get(row.index) {
+            Some(split) => {
+                text.sections[0].style.color = Color::WHITE;
+                text.sections[1].value = format_time(ticks_to_seconds(*split, run.tick_rate));
+                text.sections[1].style.color = Color::WHITE;
+
+                let delta = personal_best.map(|best| (*split as i64) - (best as i64));
+                text.sections[2].value = match delta {
+                    Some(delta) => format!("    {}", format_delta(delta, run.tick_rate)),
+                    None => String::new(),
+                };
+
+                let segment = run.segment(row.index).unwrap_or_default();
+                let is_best_segment = run.best_segments
+                    .get(row.index)
+                    .is_none_or(|best| segment < *best);
+                text.sections[2].style.color = if is_best_segment {
+                    BEST_SEGMENT_COLOR
+                } else if delta.is_some_and(|delta| delta <= 0) {
+                    AHEAD_COLOR
+                } else {
+                    BEHIND_COLOR
+                };
+            }
+            // not there yet, show what the personal best did
+            None => {
+                text.sections[0].style.color = UPCOMING_COLOR;
+                text.sections[1].value = match personal_best {
+                    Some(best) => format_time(ticks_to_seconds(best, run.tick_rate)),
+                    None => "-".to_string(),
+                };
+                text.sections[1].style.color = UPCOMING_COLOR;
+                text.sections[2].value = String::new();
+            }
+        }
+    }
+}
+
+// +s.mmm when behind, -s.mmm when ahead
+fn format_delta(delta_ticks: i64, tick_rate: u32) -> String {
+    let sign = if delta_ticks > 0 { "+" } else { "-" };
+    format!("{}{:.3}", sign, ticks_to_seconds(delta_ticks.unsigned_abs() as u32, tick_rate))
+}
@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+
+use crate::turret::resources::ProjectilePattern;
+
+// -- COMPONENTS --
+
+#[derive(Component)]
+pub struct Turret {
+    // seconds between volleys
+    pub interval: f32,
+    // only fires while the player's this close, always fires if none
+    pub range: Option<f32>,
+    pub pattern: ProjectilePattern,
+    // pixels a second
+    pub speed: f32,
+    pub damage: u32,
+    // seconds until it can fire again
+    pub cooldown: f32,
+    // volleys fired so far, spirals turn a bit further each one
+    pub volleys: u32,
+}
+
+#[derive(Component)]
+pub struct Projectile {
+    // pixels a second
+    pub velocity: Vec2,
+    // seconds left before it goes away on its own
+    pub lifetime: f32,
+    pub damage: u32,
+    // the turret that fired it, so it doesn't hit that on the way out
+    pub source: Entity,
+}
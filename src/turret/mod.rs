@@ -0,0 +1,42 @@
+// import bevy crates
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::PhysicsSet;
+
+// import modules
+pub mod components;
+pub mod resources;
+pub mod systems;
+
+// use modules
+use resources::*;
+use systems::*;
+use crate::level::systems::spawn_current_level;
+use crate::{ player_movement, simulation_running, AppState };
+
+pub const TURRET_SIZE: f32 = 32.0;
+pub const TURRET_COLOR: Color = Color::DARK_GRAY;
+pub const PROJECTILE_RADIUS: f32 = 6.0;
+pub const PROJECTILE_COLOR: Color = Color::ORANGE_RED;
+// seconds before a shot that hasn't hit anything goes away
+pub const PROJECTILE_LIFETIME: f32 = 6.0;
+// turrets hold fire while there's this many shots out, across every turret in the level
+pub const MAX_PROJECTILES: usize = 150;
+
+pub struct TurretPlugin;
+
+impl Plugin for TurretPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LevelTurrets>()
+            .add_systems(OnEnter(AppState::Game), spawn_turrets.after(spawn_current_level))
+            // hits are from where rapier last saw everything, so they're checked before
+            // anything moves again
+            .add_systems(
+                FixedUpdate,
+                (projectile_hits, move_projectiles, fire_turrets)
+                    .chain()
+                    .before(player_movement)
+                    .before(PhysicsSet::SyncBackend)
+                    .run_if(simulation_running)
+            );
+    }
+}
@@ -0,0 +1,166 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::default_damage;
+
+// -- LEVEL DATA --
+
+// the current level's turrets, spawned once the rest of the level's in
+#[derive(Resource, Default)]
+pub struct LevelTurrets {
+    pub turrets: Vec<TurretData>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct TurretData {
+    // center
+    pub position: (f32, f32),
+    // seconds between volleys
+    #[serde(default = "default_interval")]
+    pub interval: f32,
+    // only fires while the player's this close, always fires if none
+    #[serde(default)]
+    pub range: Option<f32>,
+    #[serde(default)]
+    pub pattern: ProjectilePattern,
+    // pixels a second
+    #[serde(default = "default_speed")]
+    pub speed: f32,
+    // each shot that hits the player takes this much off
+    #[serde(default = "default_damage")]
+    pub damage: u32,
+}
+
+fn default_interval() -> f32 {
+    1.5
+}
+
+fn default_speed() -> f32 {
+    300.0
+}
+
+// angles are degrees, 0.0 is right and 90.0 straight up
+#[derive(Deserialize, Clone, Copy, Default)]
+pub enum ProjectilePattern {
+    // one shot in a fixed direction
+    Single {
+        angle: f32,
+    },
+    // count shots fanned out evenly across spread degrees, centered on angle
+    Spread {
+        angle: f32,
+        count: u32,
+        spread: f32,
+    },
+    // one shot at wherever the player is
+    #[default]
+    Aimed,
+    // count shots spaced evenly all the way round, turned by step more each volley
+    Spiral {
+        count: u32,
+        step: f32,
+    },
+}
+
+impl ProjectilePattern {
+    // which way each shot of a volley goes
+    pub fn directions(&self, volley: u32, toward_player: Option<Vec2>) -> Vec<Vec2> {
+        let from_degrees = |angle: f32| Vec2::from_angle(angle.to_radians());
+
+        match *self {
+            ProjectilePattern::Single { angle } => vec![from_degrees(angle)],
+            ProjectilePattern::Spread { angle, count, spread } => {
+                if count <= 1 {
+                    return vec![from_degrees(angle)];
+                }
+                let gap = spread / ((count - 1) as f32);
+                (0..count)
+                    .map(|shot| from_degrees(angle - spread / 2.0 + gap * (shot as f32)))
+                    .collect()
+            }
+            ProjectilePattern::Aimed => toward_player.into_iter().collect(),
+            ProjectilePattern::Spiral { count, step } => {
+                let count = count.max(1);
+                let gap = 360.0 / (count as f32);
+                (0..count)
+                    .map(|shot| from_degrees(step * (volley as f32) + gap * (shot as f32)))
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_directions(directions: Vec<Vec2>, expected: &[Vec2]) {
+        assert_eq!(directions.len(), expected.len(), "{:?} isn't {:?}", directions, expected);
+        for (direction, expected) in directions.iter().zip(expected) {
+            assert!(
+                direction.distance(*expected) < 1e-5,
+                "{:?} isn't {:?}",
+                direction,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn single_fires_at_its_angle() {
+        let pattern = ProjectilePattern::Single { angle: 90.0 };
+
+        assert_directions(pattern.directions(0, None), &[Vec2::new(0.0, 1.0)]);
+    }
+
+    #[test]
+    fn spread_fans_out_around_its_angle() {
+        let pattern = ProjectilePattern::Spread { angle: 0.0, count: 3, spread: 180.0 };
+
+        assert_directions(
+            pattern.directions(0, None),
+            &[Vec2::new(0.0, -1.0), Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0)]
+        );
+    }
+
+    #[test]
+    fn spread_of_one_or_none_fires_once_at_its_angle() {
+        for count in [0, 1] {
+            let pattern = ProjectilePattern::Spread { angle: 180.0, count, spread: 90.0 };
+
+            assert_directions(pattern.directions(0, None), &[Vec2::new(-1.0, 0.0)]);
+        }
+    }
+
+    #[test]
+    fn aimed_only_fires_with_a_target() {
+        let toward_player = Vec2::new(0.6, 0.8);
+
+        assert_directions(
+            ProjectilePattern::Aimed.directions(0, Some(toward_player)),
+            &[toward_player]
+        );
+        assert!(ProjectilePattern::Aimed.directions(0, None).is_empty());
+    }
+
+    #[test]
+    fn spiral_turns_by_step_each_volley() {
+        let pattern = ProjectilePattern::Spiral { count: 2, step: 90.0 };
+
+        assert_directions(
+            pattern.directions(0, None),
+            &[Vec2::new(1.0, 0.0), Vec2::new(-1.0, 0.0)]
+        );
+        assert_directions(
+            pattern.directions(1, None),
+            &[Vec2::new(0.0, 1.0), Vec2::new(0.0, -1.0)]
+        );
+    }
+
+    #[test]
+    fn spiral_of_none_still_fires_once() {
+        let pattern = ProjectilePattern::Spiral { count: 0, step: 45.0 };
+
+        assert_directions(pattern.directions(2, None), &[Vec2::new(0.0, 1.0)]);
+    }
+}
@@ -0,0 +1,188 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::interpolation::components::{ Interpolated, InterpolatedVisual };
+use crate::level::components::LevelEntity;
+use crate::turret::components::*;
+use crate::turret::resources::*;
+use crate::turret::{
+    MAX_PROJECTILES,
+    PROJECTILE_COLOR,
+    PROJECTILE_LIFETIME,
+    PROJECTILE_RADIUS,
+    TURRET_COLOR,
+    TURRET_SIZE,
+};
+use crate::{ Player, PlayerDamaged };
+
+pub fn spawn_turrets(mut commands: Commands, level_turrets: Res<LevelTurrets>) {
+    for turret in level_turrets.turrets.iter() {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: TURRET_COLOR,
+                    custom_size: Some(Vec2::splat(TURRET_SIZE)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(turret.position.0, turret.position.1, 0.0),
+                ..default()
+            },
+            RigidBody::Fixed,
+            Collider::cuboid(TURRET_SIZE / 2.0, TURRET_SIZE / 2.0),
+            Turret {
+                interval: turret.interval,
+                range: turret.range,
+                pattern: turret.pattern,
+                speed: turret.speed,
+                damage: turret.damage,
+                cooldown: turret.interval,
+                volleys: 0,
+            },
+            LevelEntity {},
+        ));
+    }
+}
+
+pub fn fire_turrets(
+    mut commands: Commands,
+    time: Res<Time>,
+    player_query: Query<&Transform, With<Player>>,
+    mut turret_query: Query<(Entity, &Transform, &mut Turret), Without<Player>>,
+    projectile_query: Query<(), With<Projectile>>
+) {
+    let player = player_query
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation.truncate());
+    let mut live = projectile_query.iter().count();
+
+    for (entity, transform, mut turret) in turret_query.iter_mut() {
+        turret.cooldown = (turret.cooldown - time.delta_seconds()).max(0.0);
+        if turret.cooldown > 0.0 {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let in_range = match (turret.range, player) {
+            (Some(range), Some(player)) => position.distance(player) <= range,
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        // waits with its cooldown run out, so it fires the moment the player's in range
+        if !in_range {
+            continue;
+        }
+        turret.cooldown = turret.interval;
+
+        let toward_player = player.and_then(|player| (player - position).try_normalize());
+        for direction in turret.pattern.directions(turret.volleys, toward_player) {
+            if live >= MAX_PROJECTILES {
+                break;
+            }
+            live += 1;
+
+            spawn_projectile(
+                &mut commands,
+                position,
+                direction * turret.speed,
+                turret.damage,
+                entity
+            );
+        }
+        turret.volleys += 1;
+    }
+}
+
+// a shot from source, which it won't hit on the way out
+pub fn spawn_projectile(
+    commands: &mut Commands,
+    position: Vec2,
+    velocity: Vec2,
+    damage: u32,
+    source: Entity
+) {
+    commands
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_translation(position.extend(0.0))),
+            Interpolated::new(position),
+            Projectile {
+                velocity,
+                lifetime: PROJECTILE_LIFETIME,
+                damage,
+                source,
+            },
+            RigidBody::KinematicPositionBased,
+            Collider::ball(PROJECTILE_RADIUS),
+            Sensor,
+            // kinematic bodies don't notice the level's fixed or moving ones unless asked to
+            ActiveCollisionTypes::default() |
+                ActiveCollisionTypes::KINEMATIC_STATIC |
+                ActiveCollisionTypes::KINEMATIC_KINEMATIC,
+            LevelEntity {},
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: PROJECTILE_COLOR,
+                        custom_size: Some(Vec2::splat(PROJECTILE_RADIUS * 2.0)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                InterpolatedVisual {},
+            ));
+        });
+}
+
+pub fn move_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut projectile_query: Query<(Entity, &mut Transform, &mut Projectile)>
+) {
+    for (entity, mut transform, mut projectile) in projectile_query.iter_mut() {
+        projectile.lifetime -= time.delta_seconds();
+        if projectile.lifetime <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        transform.translation += (projectile.velocity * time.delta_seconds()).extend(0.0);
+    }
+}
+
+// solid level geometry stops a shot, and so does the player, who gets hurt by it
+pub fn projectile_hits(
+    mut commands: Commands,
+    rapier_context: Res<RapierContext>,
+    player_query: Query<Entity, With<Player>>,
+    projectile_query: Query<(Entity, &Transform, &Projectile)>,
+    solid_query: Query<&RigidBody, Without<Sensor>>,
+    mut player_damaged_event_writer: EventWriter<PlayerDamaged>
+) {
+    let player = player_query.get_single().ok();
+
+    for (entity, transform, projectile) in projectile_query.iter() {
+        for (collider1, collider2, intersecting) in rapier_context.intersection_pairs_with(entity) {
+            let other = if collider1 == entity { collider2 } else { collider1 };
+            if !intersecting || other == projectile.source {
+                continue;
+            }
+
+            if player == Some(other) {
+                player_damaged_event_writer.send(PlayerDamaged {
+                    damage: projectile.damage,
+                    source: transform.translation.truncate(),
+                });
+            } else if
+                !matches!(
+                    solid_query.get(other),
+                    Ok(RigidBody::Fixed | RigidBody::KinematicPositionBased)
+                )
+            {
+                continue;
+            }
+            commands.entity(entity).despawn_recursive();
+            break;
+        }
+    }
+}